@@ -0,0 +1,310 @@
+//! Derived components match all the selectors of a component in a single walk over its subtree,
+//! `old_codegen` holds the same components extracted the way the derive used to generate them - one walk per field.
+//! Both run on a synthetic catalogue and on a saved real-world page, the rustdoc page of `std::collections::HashMap`
+//! (`fixtures/std-hashmap.html`, Rust 1.95 documentation, MIT/Apache-2.0).
+use {
+    criterion::{BenchmarkId, Criterion, criterion_group, criterion_main},
+    scraper_component::{Component, Parsed, TryFromElement, scraper::Html},
+    std::hint::black_box,
+};
+
+// fields are only extracted, never read
+#[allow(dead_code)]
+#[derive(Component, Debug)]
+struct Product {
+    #[component(selector = "h2.title")]
+    title: String,
+    #[component(selector = "a.link", attr = "href")]
+    link: String,
+    #[component(selector = "img", attr = "src")]
+    image: String,
+    #[component(selector = ".price .amount")]
+    price: Parsed<f64>,
+    #[component(selector = ".price .currency")]
+    currency: String,
+    #[component(selector = ".sku")]
+    sku: String,
+    #[component(selector = ".seller a")]
+    seller: String,
+    #[component(selector = ".rating", attr = "data-score")]
+    rating: Parsed<f32>,
+    #[component(selector = ".stock")]
+    stock: String,
+    #[component(selector = "ul.badges li", many)]
+    badges: Vec<String>,
+    #[component(selector = "p.description")]
+    description: String,
+}
+
+#[allow(dead_code)]
+#[derive(Component, Debug)]
+struct Catalogue {
+    #[component(selector = "h1")]
+    heading: String,
+    #[component(selector = "article.product", many)]
+    products: Vec<Product>,
+}
+
+#[allow(dead_code)]
+#[derive(Component, Debug)]
+struct Method {
+    #[component(selector = "h4.code-header a.fn")]
+    name: String,
+    #[component(selector = "h4.code-header")]
+    signature: String,
+    #[component(selector = ".since", at_most_one)]
+    since: Option<String>,
+    #[component(selector = "a.src", attr = "href", at_most_one)]
+    source: Option<String>,
+    #[component(selector = ".docblock > p", many)]
+    paragraphs: Vec<String>,
+    #[component(selector = ".docblock pre.rust", many)]
+    examples: Vec<String>,
+}
+
+#[allow(dead_code)]
+#[derive(Component, Debug)]
+struct ApiPage {
+    #[component(selector = "h1")]
+    title: String,
+    #[component(selector = ".sidebar-elems a", many)]
+    sidebar: Vec<String>,
+    #[component(selector = "details.method-toggle", many)]
+    methods: Vec<Method>,
+}
+
+/// The components above as the derive expanded them before single-pass matching (cleaned up, one macro call per field):
+/// every field holds its selectors in a `thread_local!`, walks the whole subtree with `select` through a boxed iterator
+/// and is collected exactly like today
+#[allow(dead_code)]
+mod old_codegen {
+    use scraper_component::{
+        ComponentError,
+        Parsed,
+        Result,
+        TryCollectFrom,
+        TryFromElement,
+        attribute,
+        scraper::{ElementRef, Selector, element_ref::Select},
+        try_collect_at_most_one,
+        try_from_element,
+    };
+
+    /// `select_first_matching` back when selectors were held in a `Vec<Selector>`
+    fn select_first_matching<'document, 'selector>(element: ElementRef<'document>, selectors: &'selector [Selector]) -> Select<'document, 'selector> {
+        selectors
+            .iter()
+            .find(|selector| element.select(selector).next().is_some())
+            .or(selectors.first())
+            .map(|selector| element.select(selector))
+            .expect("at least one selector")
+    }
+
+    /// `let field = { ... }?;` of the old expansion, `many` items are attributed to their index
+    macro_rules! field {
+        ($element:ident . $field:literal = $selector:literal, $map:expr, $($many:ident)? |$mapped:ident| $collect:expr) => {{
+            thread_local! {
+                static SELECTORS: Vec<Selector> = vec![Selector::parse($selector).expect("valid selector")];
+            }
+            let scope = Some($element);
+            SELECTORS
+                .with(|selectors| {
+                    let select = match (scope, selectors.as_slice()) {
+                        (None, _) => Box::new(std::iter::empty()) as Box<dyn Iterator<Item = _>>,
+                        (Some(scope), []) => Box::new(std::iter::once(scope)),
+                        (Some(scope), selectors) => Box::new(select_first_matching(scope, selectors)),
+                    };
+                    let $mapped = field!(@map select, $map, $($many)?);
+                    $collect
+                })
+                .map_err(|error| error.at_field($field).located($selector, $element))?
+        }};
+        (@map $select:ident, $map:expr,) => {
+            $select.map($map).map(|item| item.map_err(ComponentError::from))
+        };
+        (@map $select:ident, $map:expr, many) => {
+            $select
+                .map($map)
+                .enumerate()
+                .map(|(index, item)| item.map_err(|error| ComponentError::from(error).at_index(index)))
+        };
+    }
+
+    fn one<T>(mapped: impl Iterator<Item = Result<T>>) -> Result<T> {
+        <[T; 1]>::try_collect(mapped).map(|[value]| value)
+    }
+
+    pub struct Product {
+        title: String,
+        link: String,
+        image: String,
+        price: Parsed<f64>,
+        currency: String,
+        sku: String,
+        seller: String,
+        rating: Parsed<f32>,
+        stock: String,
+        badges: Vec<String>,
+        description: String,
+    }
+
+    impl<'document> TryFromElement<'document> for Product {
+        fn try_from_element(element: ElementRef<'document>) -> Result<Self> {
+            (|| -> Result<Self> {
+                Ok(Self {
+                    title: field!(element."title" = "h2.title", try_from_element, |mapped| one(mapped)),
+                    link: field!(element."link" = "a.link", |element| attribute::attr(element, "href"), |mapped| one(mapped)),
+                    image: field!(element."image" = "img", |element| attribute::attr(element, "src"), |mapped| one(mapped)),
+                    price: field!(element."price" = ".price .amount", try_from_element, |mapped| one(mapped)),
+                    currency: field!(element."currency" = ".price .currency", try_from_element, |mapped| one(mapped)),
+                    sku: field!(element."sku" = ".sku", try_from_element, |mapped| one(mapped)),
+                    seller: field!(element."seller" = ".seller a", try_from_element, |mapped| one(mapped)),
+                    rating: field!(element."rating" = ".rating", |element| attribute::attr(element, "data-score"), |mapped| one(mapped)),
+                    stock: field!(element."stock" = ".stock", try_from_element, |mapped| one(mapped)),
+                    badges: field!(element."badges" = "ul.badges li", try_from_element, many |mapped| Vec::try_collect(mapped)),
+                    description: field!(element."description" = "p.description", try_from_element, |mapped| one(mapped)),
+                })
+            })()
+            .map_err(|error| error.in_component("Product"))
+        }
+    }
+
+    pub struct Catalogue {
+        heading: String,
+        pub products: Vec<Product>,
+    }
+
+    impl<'document> TryFromElement<'document> for Catalogue {
+        fn try_from_element(element: ElementRef<'document>) -> Result<Self> {
+            (|| -> Result<Self> {
+                Ok(Self {
+                    heading: field!(element."heading" = "h1", try_from_element, |mapped| one(mapped)),
+                    products: field!(element."products" = "article.product", try_from_element, many |mapped| Vec::try_collect(mapped)),
+                })
+            })()
+            .map_err(|error| error.in_component("Catalogue"))
+        }
+    }
+
+    pub struct Method {
+        name: String,
+        signature: String,
+        since: Option<String>,
+        source: Option<String>,
+        paragraphs: Vec<String>,
+        examples: Vec<String>,
+    }
+
+    impl<'document> TryFromElement<'document> for Method {
+        fn try_from_element(element: ElementRef<'document>) -> Result<Self> {
+            (|| -> Result<Self> {
+                Ok(Self {
+                    name: field!(element."name" = "h4.code-header a.fn", try_from_element, |mapped| one(mapped)),
+                    signature: field!(element."signature" = "h4.code-header", try_from_element, |mapped| one(mapped)),
+                    since: field!(element."since" = ".since", try_from_element, |mapped| try_collect_at_most_one(mapped)),
+                    source: field!(element."source" = "a.src", |element| attribute::attr(element, "href"), |mapped| try_collect_at_most_one(mapped)),
+                    paragraphs: field!(element."paragraphs" = ".docblock > p", try_from_element, many |mapped| Vec::try_collect(mapped)),
+                    examples: field!(element."examples" = ".docblock pre.rust", try_from_element, many |mapped| Vec::try_collect(mapped)),
+                })
+            })()
+            .map_err(|error| error.in_component("Method"))
+        }
+    }
+
+    pub struct ApiPage {
+        title: String,
+        sidebar: Vec<String>,
+        pub methods: Vec<Method>,
+    }
+
+    impl<'document> TryFromElement<'document> for ApiPage {
+        fn try_from_element(element: ElementRef<'document>) -> Result<Self> {
+            (|| -> Result<Self> {
+                Ok(Self {
+                    title: field!(element."title" = "h1", try_from_element, |mapped| one(mapped)),
+                    sidebar: field!(element."sidebar" = ".sidebar-elems a", try_from_element, many |mapped| Vec::try_collect(mapped)),
+                    methods: field!(element."methods" = "details.method-toggle", try_from_element, many |mapped| Vec::try_collect(mapped)),
+                })
+            })()
+            .map_err(|error| error.in_component("ApiPage"))
+        }
+    }
+}
+
+fn catalogue(products: usize) -> String {
+    let products = (0..products)
+        .map(|idx| {
+            format!(
+                r#"
+<article class="product" id="product-{idx}">
+    <div class="media"><a class="link" href="/products/{idx}"><img src="/img/{idx}.jpg" alt="Product {idx}"></a></div>
+    <div class="body">
+        <h2 class="title">Product {idx}</h2>
+        <div class="price"><span class="amount">{idx}.99</span> <span class="currency">EUR</span></div>
+        <div class="meta">
+            <span class="sku">SKU-{idx:05}</span>
+            <span class="seller">by <a href="/sellers/{seller}">Seller {seller}</a></span>
+            <span class="rating" data-score="4.5"><i></i><i></i><i></i><i></i></span>
+            <span class="stock">In stock</span>
+        </div>
+        <ul class="badges"><li>New</li><li>Free shipping</li><li>Bestseller</li></ul>
+        <p class="description">A <b>very</b> useful product, with <em>plenty</em> of markup around it.</p>
+    </div>
+</article>"#,
+                seller = idx % 17
+            )
+        })
+        .collect::<String>();
+    format!(
+        r#"<!DOCTYPE html><html><head><title>Catalogue</title></head><body>
+<nav><ul>{nav}</ul></nav>
+<main><h1>Catalogue</h1><section class="products">{products}</section></main>
+<footer>{footer}</footer>
+</body></html>"#,
+        nav = (0..50)
+            .map(|idx| format!(r#"<li><a href="/c/{idx}">Category {idx}</a></li>"#))
+            .collect::<String>(),
+        footer = (0..50)
+            .map(|idx| format!("<p>Footer link {idx}</p>"))
+            .collect::<String>(),
+    )
+}
+
+fn traversal(c: &mut Criterion) {
+    let mut group = c.benchmark_group("catalogue");
+    for products in [10, 100, 1000] {
+        let html = Html::parse_document(&catalogue(products));
+        group.bench_with_input(BenchmarkId::new("single_pass", products), &html, |b, html| {
+            b.iter(|| {
+                let catalogue = Catalogue::try_from_element(html.root_element()).expect("catalogue parses");
+                black_box(catalogue.products.len())
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("old_codegen", products), &html, |b, html| {
+            b.iter(|| {
+                let catalogue = old_codegen::Catalogue::try_from_element(html.root_element()).expect("catalogue parses");
+                black_box(catalogue.products.len())
+            })
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("std_hashmap_page");
+    let html = Html::parse_document(include_str!("fixtures/std-hashmap.html"));
+    group.bench_function("single_pass", |b| {
+        b.iter(|| {
+            let page = ApiPage::try_from_element(html.root_element()).expect("page parses");
+            black_box(page.methods.len())
+        })
+    });
+    group.bench_function("old_codegen", |b| {
+        b.iter(|| {
+            let page = old_codegen::ApiPage::try_from_element(html.root_element()).expect("page parses");
+            black_box(page.methods.len())
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, traversal);
+criterion_main!(benches);
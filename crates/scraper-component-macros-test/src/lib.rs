@@ -2,7 +2,7 @@
 #[cfg(test)]
 mod tests {
     use {
-        scraper_component::{TryFromElement, anyhow::Result, scraper::Html},
+        scraper_component::{ComponentError, TryFromElement, anyhow::Result, scraper::Html},
         tap::Pipe,
     };
 
@@ -15,11 +15,1116 @@ mod tests {
             html.root_element()
                 .pipe(TryFromElement::try_from_element)
                 .map(with)
+                .map_err(Into::into)
         })
     }
+
+    fn error<T>(fragment: &'static str) -> ComponentError
+    where
+        T: for<'a> TryFromElement<'a> + std::fmt::Debug,
+    {
+        Html::parse_fragment(fragment).pipe_ref(|html| T::try_from_element(html.root_element()).expect_err("parsing should fail"))
+    }
     pub mod impl_struct {
         use scraper_component::Component;
 
+        pub mod skip_and_default {
+            use {
+                super::*,
+                scraper_component::{
+                    Parsed,
+                    anyhow::{self, Result},
+                },
+            };
+
+            #[rustfmt::skip]
+            const HTML: &str = r#"
+<!DOCTYPE html>
+<body>
+    <h1>Anvil</h1>
+    <span class="price">12</span>
+</body>
+"#;
+
+            fn scraped_at() -> &'static str {
+                "2024-01-01"
+            }
+
+            fn no_discount() -> String {
+                "no discount".into()
+            }
+
+            #[derive(Component, Debug)]
+            struct Product {
+                #[component(selector = "h1")]
+                title: String,
+                #[component(selector = ".price")]
+                price: Parsed<u32>,
+                #[component(selector = ".subtitle", default)]
+                subtitle: String,
+                #[component(selector = ".discount", default = "no_discount")]
+                discount: String,
+                #[component(skip)]
+                retries: u8,
+                #[component(skip, default = "scraped_at")]
+                scraped_at: &'static str,
+            }
+
+            #[test]
+            fn test_parses() -> Result<()> {
+                super::super::parsed::<Product, _>(HTML, |product| {
+                    anyhow::ensure!(product.title == "Anvil");
+                    anyhow::ensure!(product.price.0 == 12);
+                    anyhow::ensure!(product.subtitle.is_empty());
+                    anyhow::ensure!(product.discount == "no discount");
+                    anyhow::ensure!(product.retries == 0);
+                    anyhow::ensure!(product.scraped_at == "2024-01-01");
+                    Ok(())
+                })
+                .flatten()
+            }
+        }
+
+        pub mod extraction_targets {
+            use {
+                super::*,
+                scraper_component::{
+                    Parsed,
+                    anyhow::{self, Result},
+                },
+            };
+
+            #[rustfmt::skip]
+            const HTML: &str = r#"
+<!DOCTYPE html>
+<body>
+    <a href="/first" data-rank="1">First</a>
+    <a href="/second" data-rank="2">Second</a>
+    <div class="description">Heavy <b>and</b> cheap</div>
+</body>
+"#;
+
+            #[derive(Component, Debug)]
+            struct Page {
+                #[component(selector = "a", many, attr = "href")]
+                links: Vec<String>,
+                #[component(selector = "a", many, attr = "data-rank")]
+                ranks: Vec<Parsed<u8>>,
+                #[component(selector = "a", many, attr = "title")]
+                titles: Vec<Option<String>>,
+                #[component(selector = ".description", attr = "id")]
+                id: Option<String>,
+                #[component(selector = ".description", inner_html)]
+                inner: String,
+                #[component(selector = ".description", outer_html)]
+                outer: String,
+                #[component(selector = ".description", own_text)]
+                own: String,
+            }
+
+            #[test]
+            fn test_parses() -> Result<()> {
+                super::super::parsed::<Page, _>(HTML, |page| {
+                    anyhow::ensure!(page.links == ["/first", "/second"]);
+                    anyhow::ensure!(page.ranks.iter().map(|Parsed(rank)| *rank).eq([1, 2]));
+                    anyhow::ensure!(page.titles == [None, None]);
+                    anyhow::ensure!(page.id.is_none());
+                    anyhow::ensure!(page.inner == "Heavy <b>and</b> cheap");
+                    anyhow::ensure!(page.outer == r#"<div class="description">Heavy <b>and</b> cheap</div>"#);
+                    anyhow::ensure!(page.own == "Heavy  cheap");
+                    Ok(())
+                })
+                .flatten()
+            }
+        }
+
+        pub mod regex_captures {
+            use {
+                super::*,
+                scraper_component::{
+                    Captures,
+                    ErrorKind,
+                    Parsed,
+                    anyhow::{self, Result},
+                },
+            };
+
+            #[rustfmt::skip]
+            const HTML: &str = r#"
+<!DOCTYPE html>
+<body>
+    <div class="meta">SKU: AB-123, sold 1234 units</div>
+    <a href="/products?page=7">next</a>
+</body>
+"#;
+
+            #[derive(Captures, Debug)]
+            struct Sku {
+                series: String,
+                #[capture(group = "no")]
+                number: Parsed<u32>,
+                suffix: Option<String>,
+            }
+
+            #[derive(Component, Debug)]
+            struct Product {
+                #[component(selector = ".meta", regex = r"SKU: (\S+),")]
+                sku_text: String,
+                #[component(selector = ".meta", regex = r"sold (\d+) units")]
+                sold: Parsed<u32>,
+                #[component(selector = ".meta", regex = r"SKU: (?P<series>[A-Z]+)-(?P<no>\d+)(?P<suffix>/\w+)?")]
+                sku: Sku,
+                #[component(selector = "a", attr = "href", regex = r"page=(\d+)")]
+                next_page: Parsed<u8>,
+            }
+
+            #[derive(Component, Debug)]
+            struct Missing {
+                #[component(selector = ".meta", regex = r"EAN: (\d+)")]
+                #[allow(dead_code)]
+                ean: String,
+            }
+
+            #[test]
+            fn test_parses() -> Result<()> {
+                super::super::parsed::<Product, _>(HTML, |product| {
+                    anyhow::ensure!(product.sku_text == "AB-123");
+                    anyhow::ensure!(product.sold.0 == 1234);
+                    anyhow::ensure!(product.sku.series == "AB" && product.sku.number.0 == 123 && product.sku.suffix.is_none());
+                    anyhow::ensure!(product.next_page.0 == 7);
+                    Ok(())
+                })
+                .flatten()
+            }
+
+            #[test]
+            fn test_reports_unmatched_text() {
+                let err = super::super::error::<Missing>(HTML);
+                assert_eq!(err.path(), "Missing::ean");
+                assert!(
+                    matches!(err.kind(), ErrorKind::Parse { text, .. } if text == "SKU: AB-123, sold 1234 units"),
+                    "{err}"
+                );
+            }
+        }
+
+        pub mod root_selector {
+            use {
+                super::*,
+                scraper_component::{
+                    Count,
+                    ErrorKind,
+                    anyhow::{self, Result},
+                },
+            };
+
+            #[rustfmt::skip]
+            const PRODUCT_PAGE: &str = r#"
+<!DOCTYPE html>
+<body>
+    <h1>Shop</h1>
+    <article class="product"><h1>Anvil</h1></article>
+</body>
+"#;
+
+            #[rustfmt::skip]
+            const LISTING_PAGE: &str = r#"
+<!DOCTYPE html>
+<body>
+    <article class="product"><h1>Anvil</h1></article>
+    <article class="product"><h1>Rocket skates</h1></article>
+</body>
+"#;
+
+            #[derive(Component, Debug)]
+            #[component(root = "article.product")]
+            struct Product {
+                #[component(selector = "h1")]
+                title: String,
+            }
+
+            #[derive(Component, Debug)]
+            struct Listing {
+                #[component(selector = "article.product", many)]
+                products: Vec<Product>,
+            }
+
+            #[test]
+            fn test_parses() -> Result<()> {
+                super::super::parsed::<Product, _>(PRODUCT_PAGE, |product| {
+                    anyhow::ensure!(product.title == "Anvil");
+                    Ok(())
+                })
+                .flatten()?;
+                super::super::parsed::<Listing, _>(LISTING_PAGE, |listing| {
+                    anyhow::ensure!(
+                        listing
+                            .products
+                            .iter()
+                            .map(|p| p.title.as_str())
+                            .eq(["Anvil", "Rocket skates"])
+                    );
+                    Ok(())
+                })
+                .flatten()
+            }
+
+            #[test]
+            fn test_ambiguous_root() {
+                let err = super::super::error::<Product>(LISTING_PAGE);
+                assert_eq!(err.path(), "Product");
+                assert_eq!(err.selector(), Some("article.product"));
+                assert_eq!(
+                    err.kind(),
+                    &ErrorKind::TooMany {
+                        expected: Count::exactly(1),
+                        found: 2
+                    }
+                );
+            }
+        }
+
+        pub mod flatten {
+            use {
+                super::*,
+                scraper_component::{
+                    Count,
+                    ErrorKind,
+                    Parsed,
+                    anyhow::{self, Result},
+                },
+            };
+
+            #[rustfmt::skip]
+            const HTML: &str = r#"
+<!DOCTYPE html>
+<body>
+    <h1>Anvil</h1>
+    <span class="amount">12</span>
+    <span class="currency">EUR</span>
+</body>
+"#;
+
+            #[derive(Component, Debug)]
+            struct Pricing {
+                #[component(selector = ".amount")]
+                amount: Parsed<u32>,
+                #[component(selector = ".currency")]
+                currency: String,
+            }
+
+            #[derive(Component, Debug)]
+            struct ProductPage {
+                #[component(selector = "h1")]
+                title: String,
+                #[component(flatten)]
+                pricing: Pricing,
+            }
+
+            #[derive(Component, Debug)]
+            struct BrokenPricing {
+                #[component(selector = ".price-amount")]
+                #[allow(dead_code)]
+                amount: Parsed<u32>,
+            }
+
+            #[derive(Component, Debug)]
+            struct BrokenPage {
+                #[component(flatten)]
+                #[allow(dead_code)]
+                pricing: BrokenPricing,
+            }
+
+            #[test]
+            fn test_parses() -> Result<()> {
+                super::super::parsed::<ProductPage, _>(HTML, |page| {
+                    anyhow::ensure!(page.title == "Anvil");
+                    anyhow::ensure!(page.pricing.amount.0 == 12 && page.pricing.currency == "EUR");
+                    Ok(())
+                })
+                .flatten()
+            }
+
+            #[test]
+            fn test_errors_attributed_to_parent() {
+                let err = super::super::error::<BrokenPage>(HTML);
+                assert_eq!(err.path(), "BrokenPage::pricing::amount");
+                assert_eq!(
+                    err.kind(),
+                    &ErrorKind::Missing {
+                        expected: Count::exactly(1),
+                        found: 0
+                    }
+                );
+            }
+        }
+
+        pub mod cardinality {
+            use {
+                super::*,
+                scraper_component::{
+                    Bounded,
+                    Count,
+                    ErrorKind,
+                    anyhow::{self, Result},
+                },
+                std::collections::HashSet,
+            };
+
+            #[rustfmt::skip]
+            const HTML: &str = r#"
+<!DOCTYPE html>
+<body>
+    <li>one</li>
+    <li>two</li>
+    <li>three</li>
+    <span class="badge">new</span>
+</body>
+"#;
+
+            #[derive(Component, Debug)]
+            struct Items {
+                #[component(selector = "li", first)]
+                first: String,
+                #[component(selector = "li", last)]
+                last: String,
+                #[component(selector = "li", nth = 1)]
+                second: String,
+                #[component(selector = "li", nth = 5, many)]
+                sixth: Option<String>,
+                #[component(selector = ".badge", at_most_one)]
+                badge: Option<String>,
+                #[component(selector = ".ribbon", at_most_one)]
+                ribbon: Option<String>,
+            }
+
+            #[derive(Component, Debug)]
+            struct StrictItem {
+                #[component(selector = "li", at_most_one)]
+                #[allow(dead_code)]
+                item: Option<String>,
+            }
+
+            #[test]
+            fn test_parses() -> Result<()> {
+                super::super::parsed::<Items, _>(HTML, |items| {
+                    anyhow::ensure!(items.first == "one");
+                    anyhow::ensure!(items.last == "three");
+                    anyhow::ensure!(items.second == "two");
+                    anyhow::ensure!(items.sixth.is_none());
+                    anyhow::ensure!(items.badge.as_deref() == Some("new"));
+                    anyhow::ensure!(items.ribbon.is_none());
+                    Ok(())
+                })
+                .flatten()
+            }
+
+            #[derive(Component, Debug)]
+            struct Bounds {
+                #[component(selector = "li", many, min = 2, max = 50)]
+                items: Vec<String>,
+                #[component(selector = "li", many)]
+                bounded: Bounded<Vec<String>, 1, 3>,
+                #[component(selector = "li", many, duplicates = "first", max = 3)]
+                distinct: HashSet<String>,
+            }
+
+            #[derive(Component, Debug)]
+            struct HalfBroken {
+                #[component(selector = ".product", many, min = 2, max = 50)]
+                #[allow(dead_code)]
+                products: Vec<String>,
+            }
+
+            #[derive(Component, Debug)]
+            struct Capped {
+                #[component(selector = "li", many)]
+                #[allow(dead_code)]
+                items: Bounded<Vec<String>, 0, 2>,
+            }
+
+            #[test]
+            fn test_bounded_counts() -> Result<()> {
+                super::super::parsed::<Bounds, _>(HTML, |bounds| {
+                    anyhow::ensure!(bounds.items.len() == 3);
+                    anyhow::ensure!(bounds.bounded.len() == 3);
+                    anyhow::ensure!(bounds.distinct.len() == 3);
+                    Ok(())
+                })
+                .flatten()?;
+                let err = super::super::error::<HalfBroken>(HTML);
+                assert_eq!(
+                    err.kind(),
+                    &ErrorKind::Missing {
+                        expected: Count { min: 2, max: Some(50) },
+                        found: 0
+                    }
+                );
+                assert_eq!(err.to_string(), "HalfBroken::products: expected 2..=50, found 0 (selector: .product)");
+                let err = super::super::error::<Capped>(HTML);
+                assert_eq!(
+                    err.kind(),
+                    &ErrorKind::TooMany {
+                        expected: Count::at_most(2),
+                        found: 3
+                    }
+                );
+                Ok(())
+            }
+
+            // `filter_value` predicates take a reference to the value itself
+            #[allow(clippy::ptr_arg)]
+            fn is_long(item: &String) -> bool {
+                item.len() > 3
+            }
+
+            #[derive(Component, Debug)]
+            struct Cleaned {
+                #[component(selector = "li", many, min = 3, max = 3, filter_value = is_long)]
+                long: Vec<String>,
+                #[component(selector = "li", many, min = 3, max = 3, limit = 1)]
+                limited: Vec<String>,
+            }
+
+            #[test]
+            fn test_bounds_count_matched_elements() -> Result<()> {
+                super::super::parsed::<Cleaned, _>(HTML, |cleaned| {
+                    anyhow::ensure!(cleaned.long == ["three"]);
+                    anyhow::ensure!(cleaned.limited == ["one"]);
+                    Ok(())
+                })
+                .flatten()
+            }
+
+            #[test]
+            fn test_at_most_one_rejects_many() {
+                let err = super::super::error::<StrictItem>(HTML);
+                assert_eq!(
+                    err.kind(),
+                    &ErrorKind::TooMany {
+                        expected: Count::at_most(1),
+                        found: 3
+                    }
+                );
+                assert_eq!(err.to_string(), "StrictItem::item: expected at most 1, found 3 (selector: li)");
+            }
+        }
+
+        pub mod keyed_collections {
+            use {
+                super::*,
+                indexmap::IndexMap,
+                scraper_component::{
+                    ErrorKind,
+                    anyhow::{self, Result},
+                },
+                std::collections::{BTreeMap, HashMap},
+            };
+
+            #[rustfmt::skip]
+            const HTML: &str = r#"
+<!DOCTYPE html>
+<body>
+    <table class="specs">
+        <tr><th>Weight</th><td>12kg</td></tr>
+        <tr><th>Colour</th><td>Black</td></tr>
+        <tr><th>Weight</th><td>13kg</td></tr>
+    </table>
+    <select>
+        <option value="s">Small</option>
+        <option value="m">Medium</option>
+        <option value="l">Large</option>
+    </select>
+</body>
+"#;
+
+            #[derive(Component, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+            struct SpecName(#[component(selector = "th")] String);
+
+            #[derive(Component, Debug)]
+            struct SpecValue(#[component(selector = "td")] String);
+
+            #[derive(Component, Debug)]
+            struct Spec {
+                #[component(selector = "th")]
+                name: String,
+                #[component(selector = "td")]
+                value: String,
+            }
+
+            fn spec_name(spec: &Spec) -> String {
+                spec.name.clone()
+            }
+
+            #[derive(Component, Debug)]
+            struct Choice {
+                #[component(attr = "value")]
+                value: String,
+                label: String,
+            }
+
+            fn choice_value(choice: &Choice) -> String {
+                choice.value.clone()
+            }
+
+            fn by_label(a: &Choice, b: &Choice) -> std::cmp::Ordering {
+                a.label.cmp(&b.label)
+            }
+
+            fn not_small(choice: &Choice) -> bool {
+                choice.label != "Small"
+            }
+
+            #[derive(Component, Debug)]
+            struct Page {
+                #[component(selector = ".specs tr", many, duplicates = "first")]
+                first: HashMap<SpecName, SpecValue>,
+                #[component(selector = ".specs tr", many, duplicates = "last")]
+                last: BTreeMap<SpecName, SpecValue>,
+                #[component(selector = ".specs tr", many, key = spec_name, duplicates = "last")]
+                specs: IndexMap<String, Spec>,
+                #[component(selector = "option", many, key = choice_value)]
+                choices: IndexMap<String, Choice>,
+                #[component(selector = "option", many, key = choice_value, filter_value = not_small, sort_by = by_label)]
+                sorted: IndexMap<String, Choice>,
+            }
+
+            #[derive(Component, Debug)]
+            struct Strict {
+                #[component(selector = ".specs tr", many)]
+                specs: HashMap<SpecName, SpecValue>,
+            }
+
+            mod keys {
+                pub(super) fn spec_value(spec: &super::Spec) -> String {
+                    spec.value.clone()
+                }
+            }
+
+            #[derive(Component, Debug)]
+            struct ByValue {
+                #[component(selector = ".specs tr", many, key = keys::spec_value)]
+                specs: BTreeMap<String, Spec>,
+            }
+
+            #[test]
+            fn test_parses() -> Result<()> {
+                super::super::parsed::<Page, _>(HTML, |page| {
+                    anyhow::ensure!(page.first[&SpecName("Weight".into())].0 == "12kg");
+                    anyhow::ensure!(page.last[&SpecName("Weight".into())].0 == "13kg");
+                    anyhow::ensure!(page.specs.keys().collect::<Vec<_>>() == ["Weight", "Colour"]);
+                    anyhow::ensure!(page.specs["Weight"].value == "13kg");
+                    anyhow::ensure!(page.choices.keys().collect::<Vec<_>>() == ["s", "m", "l"]);
+                    anyhow::ensure!(page.choices["m"].label == "Medium");
+                    anyhow::ensure!(page.sorted.keys().collect::<Vec<_>>() == ["l", "m"]);
+                    Ok(())
+                })
+                .flatten()
+            }
+
+            #[test]
+            fn test_rejects_duplicate_keys() {
+                let err = super::super::error::<Strict>(HTML);
+                assert_eq!(
+                    err.kind(),
+                    &ErrorKind::DuplicateKey {
+                        key: r#"SpecName("Weight")"#.into()
+                    }
+                );
+                assert_eq!(err.path(), "Strict::specs[2]");
+            }
+
+            #[test]
+            fn test_key_by_path() -> Result<()> {
+                super::super::parsed::<ByValue, _>(HTML, |page| {
+                    anyhow::ensure!(page.specs.keys().collect::<Vec<_>>() == ["12kg", "13kg", "Black"]);
+                    anyhow::ensure!(page.specs["Black"].name == "Colour");
+                    Ok(())
+                })
+                .flatten()
+            }
+        }
+
+        pub mod collection_targets {
+            use {
+                super::*,
+                arrayvec::ArrayVec,
+                indexmap::IndexSet,
+                scraper_component::{
+                    Count,
+                    ErrorKind,
+                    anyhow::{self, Result},
+                },
+                smallvec::SmallVec,
+                std::{
+                    collections::{BTreeSet, HashSet},
+                    sync::Arc,
+                },
+            };
+
+            #[rustfmt::skip]
+            const HTML: &str = r#"
+<!DOCTYPE html>
+<body>
+    <li>new</li>
+    <li>sale</li>
+    <li>new</li>
+</body>
+"#;
+
+            #[derive(Component, Debug)]
+            struct Tags {
+                #[component(selector = "li", many, duplicates = "first")]
+                hashed: HashSet<String>,
+                #[component(selector = "li", many, duplicates = "last")]
+                sorted: BTreeSet<String>,
+                #[component(selector = "li", many, duplicates = "first")]
+                ordered: IndexSet<String>,
+                #[component(selector = "li", many)]
+                boxed: Box<[String]>,
+                #[component(selector = "li", many)]
+                shared: Arc<[String]>,
+                #[component(selector = "li", many)]
+                small: SmallVec<[String; 2]>,
+                #[component(selector = "li", many)]
+                bounded: ArrayVec<String, 4>,
+            }
+
+            #[derive(Component, Debug)]
+            struct StrictSet {
+                #[component(selector = "li", many)]
+                tags: HashSet<String>,
+            }
+
+            #[derive(Component, Debug)]
+            struct Tight {
+                #[component(selector = "li", many)]
+                tags: ArrayVec<String, 2>,
+            }
+
+            #[test]
+            fn test_parses() -> Result<()> {
+                super::super::parsed::<Tags, _>(HTML, |tags| {
+                    anyhow::ensure!(tags.hashed.len() == 2);
+                    anyhow::ensure!(tags.sorted.iter().collect::<Vec<_>>() == ["new", "sale"]);
+                    anyhow::ensure!(tags.ordered.iter().collect::<Vec<_>>() == ["new", "sale"]);
+                    anyhow::ensure!(*tags.boxed == ["new", "sale", "new"]);
+                    anyhow::ensure!(*tags.shared == ["new", "sale", "new"]);
+                    anyhow::ensure!(tags.small.len() == 3 && tags.small.spilled());
+                    anyhow::ensure!(tags.bounded.as_slice() == ["new", "sale", "new"]);
+                    Ok(())
+                })
+                .flatten()
+            }
+
+            #[test]
+            fn test_rejects_duplicates_and_overflow() {
+                let err = super::super::error::<StrictSet>(HTML);
+                assert_eq!(err.kind(), &ErrorKind::DuplicateKey { key: r#""new""#.into() });
+                assert_eq!(err.path(), "StrictSet::tags[2]");
+                let err = super::super::error::<Tight>(HTML);
+                assert_eq!(
+                    err.kind(),
+                    &ErrorKind::TooMany {
+                        expected: Count::at_most(2),
+                        found: 3
+                    }
+                );
+            }
+        }
+
+        pub mod item_errors {
+            use {
+                super::*,
+                scraper_component::{
+                    ComponentError,
+                    Count,
+                    ErrorKind,
+                    Parsed,
+                    PathSegment,
+                    Tolerant,
+                    anyhow::{self, Result},
+                },
+            };
+
+            #[rustfmt::skip]
+            const HTML: &str = r#"
+<!DOCTYPE html>
+<body>
+    <table>
+        <tr><td class="name">Anvil</td><td class="price">12</td></tr>
+        <tr><td class="name">Tongs</td><td class="price">soon</td></tr>
+        <tr><td class="name">Hammer</td><td class="price">7</td></tr>
+    </table>
+</body>
+"#;
+
+            #[derive(Component, Debug)]
+            struct Row {
+                #[component(selector = ".name")]
+                name: String,
+                #[component(selector = ".price")]
+                price: Parsed<u32>,
+            }
+
+            #[derive(Component, Debug)]
+            struct Listing {
+                #[component(selector = "tr", many, skip_errors)]
+                rows: Vec<Row>,
+                #[component(selector = "tr", many)]
+                results: Vec<Result<Row, ComponentError>>,
+                #[component(selector = "tr", many)]
+                tolerant: Tolerant<Vec<Row>>,
+            }
+
+            #[derive(Component, Debug)]
+            struct AtLeastThree {
+                #[component(selector = "tr", many, skip_errors, min = 3)]
+                #[allow(dead_code)]
+                rows: Vec<Row>,
+            }
+
+            #[test]
+            fn test_tolerates_failing_items() -> Result<()> {
+                super::super::parsed::<Listing, _>(HTML, |listing| {
+                    anyhow::ensure!(
+                        listing
+                            .rows
+                            .iter()
+                            .map(|row| row.name.as_str())
+                            .collect::<Vec<_>>()
+                            == ["Anvil", "Hammer"]
+                    );
+                    anyhow::ensure!(listing.results.len() == 3);
+                    anyhow::ensure!(
+                        listing.results[0]
+                            .as_ref()
+                            .is_ok_and(|row| row.price.0 == 12)
+                    );
+                    let error = listing.results[1]
+                        .as_ref()
+                        .expect_err("price is not a number");
+                    anyhow::ensure!(matches!(error.kind(), ErrorKind::Parse { .. }));
+                    anyhow::ensure!(error.path() == "Listing::results[1]::price", "{error}");
+                    anyhow::ensure!(error.html().is_some_and(|html| html.contains("soon")));
+
+                    anyhow::ensure!(listing.tolerant.len() == 2);
+                    let [skipped] = listing.tolerant.skipped.as_slice() else {
+                        anyhow::bail!("one item is skipped: {:?}", listing.tolerant.skipped);
+                    };
+                    anyhow::ensure!(
+                        skipped
+                            .segments()
+                            .starts_with(&[PathSegment::Field("tolerant".into()), PathSegment::Index(1)])
+                    );
+                    anyhow::ensure!(skipped.path() == "Listing::tolerant[1]::price", "{skipped}");
+                    anyhow::ensure!(
+                        skipped
+                            .html()
+                            .is_some_and(|html| html.contains("Tongs") && html.contains("soon"))
+                    );
+                    Ok(())
+                })
+                .flatten()
+            }
+
+            #[test]
+            fn test_skipped_items_do_not_count() {
+                let err = super::super::error::<AtLeastThree>(HTML);
+                assert_eq!(
+                    err.kind(),
+                    &ErrorKind::Missing {
+                        expected: Count::at_least(3),
+                        found: 2
+                    }
+                );
+            }
+        }
+
+        pub mod value_pipeline {
+            use {
+                super::*,
+                scraper_component::{
+                    Parsed,
+                    anyhow::{self, Result},
+                    scraper::ElementRef,
+                },
+                std::cmp::Ordering,
+            };
+
+            #[rustfmt::skip]
+            const HTML: &str = r#"
+<!DOCTYPE html>
+<body>
+    <ul>
+        <li><a href="/b">Bravo</a><span class="score">2</span></li>
+        <li class="ad"><a href="/ad">Sponsored</a><span class="score">9</span></li>
+        <li><a href="/a">Alpha</a><span class="score">3</span></li>
+        <li><a href="/b">Bravo again</a><span class="score">1</span></li>
+        <li><a href="/c">Charlie</a><span class="score">5</span></li>
+    </ul>
+</body>
+"#;
+
+            #[derive(Component, Debug)]
+            struct Hit {
+                #[component(selector = "a", attr = "href")]
+                href: String,
+                #[component(selector = "a")]
+                title: String,
+                #[component(selector = ".score")]
+                score: Parsed<u32>,
+            }
+
+            fn not_ad(element: &ElementRef<'_>) -> bool {
+                !element.value().classes().any(|class| class == "ad")
+            }
+
+            fn is_relevant(hit: &Hit) -> bool {
+                hit.score.0 >= 2
+            }
+
+            fn by_href(hit: &Hit) -> String {
+                hit.href.clone()
+            }
+
+            fn by_score_desc(a: &Hit, b: &Hit) -> Ordering {
+                b.score.0.cmp(&a.score.0)
+            }
+
+            #[derive(Component, Debug)]
+            struct Results {
+                #[component(selector = "li", many, filter = not_ad)]
+                organic: Vec<Hit>,
+                #[component(selector = "li", filter = not_ad, last)]
+                last: Hit,
+                #[component(selector = "li a", many, attr = "href", dedup)]
+                links: Vec<String>,
+                #[component(selector = "li", many, filter = not_ad, dedup_by = by_href)]
+                unique: Vec<Hit>,
+                #[component(selector = "li", many, filter = not_ad, filter_value = is_relevant, sort_by = by_score_desc, limit = 2)]
+                best: Vec<Hit>,
+                #[component(selector = "li", many, limit = 2)]
+                first_two: Vec<Hit>,
+            }
+
+            fn titles(hits: &[Hit]) -> Vec<&str> {
+                hits.iter().map(|hit| hit.title.as_str()).collect()
+            }
+
+            #[test]
+            fn test_cleans_up_values() -> Result<()> {
+                super::super::parsed::<Results, _>(HTML, |results| {
+                    anyhow::ensure!(results.organic.len() == 4);
+                    anyhow::ensure!(results.last.title == "Charlie");
+                    anyhow::ensure!(results.links == ["/b", "/ad", "/a", "/c"]);
+                    anyhow::ensure!(titles(&results.unique) == ["Bravo", "Alpha", "Charlie"]);
+                    anyhow::ensure!(titles(&results.best) == ["Charlie", "Alpha"]);
+                    anyhow::ensure!(titles(&results.first_two) == ["Bravo", "Sponsored"]);
+                    Ok(())
+                })
+                .flatten()
+            }
+        }
+
+        pub mod aggregates {
+            use {
+                super::*,
+                scraper_component::anyhow::{self, Result},
+            };
+
+            #[rustfmt::skip]
+            const HTML: &str = r#"
+<!DOCTYPE html>
+<body>
+    <span class="tag">tools</span>
+    <span class="tag">metal</span>
+    <span class="tag">heavy</span>
+    <div class="review">great</div>
+    <div class="review">fine</div>
+    <span class="sold-out">Sold out</span>
+</body>
+"#;
+
+            #[derive(Component, Debug)]
+            struct Product {
+                #[component(selector = ".review", count)]
+                reviews: usize,
+                #[component(selector = ".question", count)]
+                questions: usize,
+                #[component(selector = ".sold-out", exists)]
+                sold_out: bool,
+                #[component(selector = ".sale", exists)]
+                on_sale: bool,
+                #[component(selector = ".tag", many, join = ", ")]
+                tags: String,
+                #[component(selector = ".tag", many, join = "|", limit = 2)]
+                first_tags: String,
+                #[component(selector = ".label", many, join = ", ")]
+                labels: String,
+            }
+
+            #[test]
+            fn test_aggregates() -> Result<()> {
+                super::super::parsed::<Product, _>(HTML, |product| {
+                    anyhow::ensure!(product.reviews == 2);
+                    anyhow::ensure!(product.questions == 0);
+                    anyhow::ensure!(product.sold_out);
+                    anyhow::ensure!(!product.on_sale);
+                    anyhow::ensure!(product.tags == "tools, metal, heavy");
+                    anyhow::ensure!(product.first_tags == "tools|metal");
+                    anyhow::ensure!(product.labels.is_empty());
+                    Ok(())
+                })
+                .flatten()
+            }
+        }
+
+        pub mod fallback_selectors {
+            use {
+                super::*,
+                scraper_component::{
+                    Parsed,
+                    anyhow::{self, Result},
+                },
+            };
+
+            #[derive(Component, Debug)]
+            struct Product {
+                #[component(selector = ".price-new", fallback = [".price", "[itemprop=price]"])]
+                price: Parsed<u32>,
+            }
+
+            #[test]
+            fn test_parses() -> Result<()> {
+                for html in [
+                    r#"<span class="price-new">1</span><span class="price">2</span>"#,
+                    r#"<span class="price">1</span>"#,
+                    r#"<span itemprop="price">1</span>"#,
+                ] {
+                    super::super::parsed::<Product, _>(html, |product| {
+                        anyhow::ensure!(product.price.0 == 1, "{html}");
+                        Ok(())
+                    })
+                    .flatten()?;
+                }
+                Ok(())
+            }
+
+            #[test]
+            fn test_reports_attempted_selectors() {
+                let err = super::super::error::<Product>(r#"<span class="cost">1</span>"#);
+                assert_eq!(err.selector(), Some(".price-new, then fallbacks: .price, [itemprop=price]"));
+            }
+        }
+
+        pub mod interned_selectors {
+            use {
+                super::*,
+                scraper_component::{
+                    Parsed,
+                    anyhow::{self, Result},
+                },
+            };
+
+            #[derive(Component, Debug)]
+            struct Offer {
+                #[component(selector = ".price-new", fallback = [".price"])]
+                price: Parsed<u32>,
+            }
+
+            #[derive(Component, Debug)]
+            struct Quote {
+                #[component(selector = ".quote", fallback = [".price"])]
+                price: Parsed<u32>,
+            }
+
+            #[test]
+            fn test_shared_across_components() -> Result<()> {
+                let html = r#"<span class="price">3</span>"#;
+                let offer = super::super::parsed::<Offer, _>(html, |offer| offer.price.0)?;
+                let quote = super::super::parsed::<Quote, _>(html, |quote| quote.price.0)?;
+                anyhow::ensure!(offer == 3 && quote == 3);
+                anyhow::ensure!(std::ptr::eq(scraper_component::selector(".price"), scraper_component::selector(".price")));
+                Ok(())
+            }
+        }
+
+        pub mod navigation {
+            use {
+                super::*,
+                scraper_component::anyhow::{self, Result},
+            };
+
+            #[rustfmt::skip]
+            const HTML: &str = r#"
+<!DOCTYPE html>
+<body>
+    <dl>
+        <dt class="weight">Weight</dt>
+        <!-- comment -->
+        <dd>12kg</dd>
+        <dt class="color">Color</dt>
+        <dd>red</dd>
+    </dl>
+    <h2>Prices</h2>
+    <p>intro</p>
+    <table class="prices">
+        <tr><td class="name">Anvil</td><td class="price"><b>12</b></td></tr>
+    </table>
+</body>
+"#;
+
+            #[derive(Component, Debug)]
+            struct Price {
+                #[component(closest = "tr", selector = "td.name")]
+                product: String,
+                // shares the `tr` selector with `product`
+                #[component(closest = "tr", selector = "td.price")]
+                price_cell: String,
+                #[component(parent)]
+                cell: String,
+                #[component(closest = "dl", many)]
+                definition_list: Option<String>,
+            }
+
+            #[derive(Component, Debug)]
+            struct Table {
+                #[component(prev_sibling = "h2")]
+                heading: String,
+                #[component(selector = "td.price b")]
+                price: Price,
+            }
+
+            #[derive(Component, Debug)]
+            struct Weight {
+                #[component(next_sibling = "dd")]
+                value: String,
+            }
+
+            #[derive(Component, Debug)]
+            struct Page {
+                #[component(selector = "dt.weight")]
+                weight: Weight,
+                #[component(selector = "table.prices")]
+                table: Table,
+            }
+
+            #[test]
+            fn test_parses() -> Result<()> {
+                super::super::parsed::<Page, _>(HTML, |page| {
+                    anyhow::ensure!(page.weight.value == "12kg");
+                    anyhow::ensure!(page.table.heading == "Prices");
+                    anyhow::ensure!(page.table.price.product == "Anvil");
+                    anyhow::ensure!(page.table.price.price_cell == "12");
+                    anyhow::ensure!(page.table.price.cell == "12");
+                    anyhow::ensure!(page.table.price.definition_list.is_none());
+                    Ok(())
+                })
+                .flatten()
+            }
+        }
+
         pub mod three_texts {
             use {
                 super::*,
@@ -79,5 +1184,564 @@ mod tests {
                 .flatten()
             }
         }
+
+        pub mod error_paths {
+            use {
+                super::*,
+                scraper_component::{ComponentError, Count, ErrorKind, Hardcoded, Parsed, PathSegment},
+                std::error::Error,
+            };
+
+            #[rustfmt::skip]
+            const HTML: &str = r#"
+<!DOCTYPE html>
+<body>
+    <h1>Catalogue</h1>
+    <ul>
+        <li><span class="price">1</span></li>
+        <li><span class="price">2</span></li>
+        <li><span class="price">3</span></li>
+        <li><span class="price">four</span></li>
+    </ul>
+</body>
+"#;
+
+            #[derive(Component, Debug)]
+            struct Item {
+                #[component(selector = ".price")]
+                price: Parsed<u32>,
+            }
+
+            #[derive(Component, Debug)]
+            struct Page {
+                #[component(selector = "h1")]
+                title: String,
+                #[component(selector = "li", many)]
+                items: Vec<Item>,
+            }
+
+            #[derive(Component, Debug)]
+            struct Shop {
+                #[component(selector = "h1")]
+                title: Hardcoded<"Shop">,
+            }
+
+            #[derive(Component, Debug)]
+            struct Pair {
+                #[component(selector = "li", many)]
+                items: [Item; 2],
+            }
+
+            #[derive(Component, Debug)]
+            #[component(accumulate_errors)]
+            struct Redesigned {
+                #[component(selector = "h2")]
+                subtitle: String,
+                #[component(selector = "h1")]
+                title: String,
+                #[component(selector = "li", many)]
+                items: Vec<Item>,
+                #[component(selector = "footer")]
+                footer: String,
+            }
+
+            #[test]
+            fn test_accumulates_errors() {
+                let err = super::super::error::<Redesigned>(HTML);
+                assert_eq!(err.kind(), &ErrorKind::Multiple);
+                assert_eq!(
+                    err.related()
+                        .iter()
+                        .map(ComponentError::path)
+                        .collect::<Vec<_>>(),
+                    ["Redesigned::subtitle", "Redesigned::items[3]::price", "Redesigned::footer"]
+                );
+                assert!(
+                    err.to_string()
+                        .contains("\n- Redesigned::footer: expected 1, found 0 (selector: footer)"),
+                    "{err}"
+                );
+            }
+
+            #[test]
+            fn test_reports_item_path() {
+                let err = super::super::error::<Page>(HTML);
+                assert_eq!(err.path(), "Page::items[3]::price");
+                assert_eq!(err.component(), Some("Page"));
+                assert_eq!(
+                    err.segments(),
+                    [PathSegment::Field("items".into()), PathSegment::Index(3), PathSegment::Field("price".into())]
+                );
+                assert_eq!(err.selector(), Some(".price"));
+                assert!(matches!(err.kind(), ErrorKind::Parse { text, .. } if text == "four"), "{err}");
+                assert_eq!(err.source().map(ToString::to_string).as_deref(), Some("invalid digit found in string"));
+            }
+
+            #[test]
+            fn test_reports_hardcoded_mismatch() {
+                let err = super::super::error::<Shop>(HTML);
+                assert_eq!(
+                    err.kind(),
+                    &ErrorKind::HardcodedMismatch {
+                        expected: "Shop",
+                        found: "Catalogue".into()
+                    }
+                );
+            }
+
+            #[test]
+            fn test_reports_counts() {
+                let err = super::super::error::<Pair>(HTML);
+                assert_eq!(err.path(), "Pair::items");
+                assert_eq!(
+                    err.kind(),
+                    &ErrorKind::TooMany {
+                        expected: Count::exactly(2),
+                        found: 4
+                    }
+                );
+            }
+        }
+
+        pub mod partial_results {
+            use {
+                super::*,
+                scraper_component::{
+                    ErrorKind,
+                    Parsed,
+                    Partial,
+                    PartialComponent,
+                    anyhow::{self, Result},
+                    scraper::Html,
+                },
+            };
+
+            #[rustfmt::skip]
+            const HTML: &str = r#"
+<!DOCTYPE html>
+<body>
+    <article class="product">
+        <h1>Anvil</h1>
+        <span class="price">twelve</span>
+        <dl><dt>Weight</dt><dd>12kg</dd></dl>
+    </article>
+</body>
+"#;
+
+            #[derive(Component, Debug)]
+            struct Spec {
+                #[component(selector = "dt")]
+                name: String,
+                #[component(selector = "dd")]
+                value: String,
+            }
+
+            #[derive(Component, Debug)]
+            #[component(partial(derive(Debug)), root = "article.product")]
+            struct Product {
+                #[component(selector = "h1")]
+                title: String,
+                #[component(selector = ".price")]
+                price: Parsed<u32>,
+                #[component(selector = "dl")]
+                spec: Spec,
+                #[component(selector = ".rating", at_most_one)]
+                rating: Option<String>,
+            }
+
+            #[derive(Component, Debug)]
+            #[component(partial)]
+            struct Heading<'d>(#[component(selector = "h1")] &'d str, #[component(selector = "h2")] &'d str);
+
+            #[derive(Component, Debug)]
+            #[component(partial)]
+            struct Titled<T = String> {
+                #[component(selector = "h1")]
+                title: T,
+                #[component(selector = ".price")]
+                price: Parsed<u32>,
+            }
+
+            #[test]
+            fn test_parses_what_it_can() -> Result<()> {
+                let html = Html::parse_fragment(HTML);
+                let product: Partial<'_, Product> = Product::try_from_element_partial(html.root_element())?;
+                anyhow::ensure!(product.title.as_deref().ok() == Some("Anvil"));
+                anyhow::ensure!(
+                    product
+                        .spec
+                        .as_ref()
+                        .is_ok_and(|spec| spec.name == "Weight" && spec.value == "12kg")
+                );
+                anyhow::ensure!(product.rating.as_ref().is_ok_and(Option::is_none));
+                let price = product.price.as_ref().expect_err("price is not a number");
+                anyhow::ensure!(price.path() == "Product::price", "{price}");
+                anyhow::ensure!(matches!(price.kind(), ErrorKind::Parse { .. }));
+
+                let err = product.complete().expect_err("price is broken");
+                anyhow::ensure!(err.path() == "Product::price", "{err}");
+
+                let heading = Heading::try_from_element_partial(html.root_element())?;
+                anyhow::ensure!(heading.0.is_ok_and(|title| title == "Anvil"));
+                anyhow::ensure!(heading.1.is_err());
+
+                let titled: TitledPartial = Titled::try_from_element_partial(html.root_element())?;
+                anyhow::ensure!(titled.title.as_deref().ok() == Some("Anvil"));
+                let err = titled.complete().expect_err("price is broken");
+                anyhow::ensure!(err.path() == "Titled::price", "{err}");
+                Ok(())
+            }
+
+            #[test]
+            fn test_root_failure_fails_everything() {
+                let html = Html::parse_fragment("<p>nothing here</p>");
+                let err = Product::try_from_element_partial(html.root_element()).expect_err("no root");
+                assert_eq!(err.path(), "Product");
+                assert_eq!(err.selector(), Some("article.product"));
+            }
+        }
+    }
+
+    pub mod impl_tuple_struct {
+        use scraper_component::Component;
+
+        pub mod newtypes {
+            use {
+                super::*,
+                scraper_component::{
+                    Parsed,
+                    anyhow::{self, Result},
+                },
+            };
+
+            #[rustfmt::skip]
+            const HTML: &str = r#"
+<!DOCTYPE html>
+<body>
+    <div class="product"><span class="price">12.5</span><a href="/anvil">Anvil</a></div>
+</body>
+"#;
+
+            #[derive(Component, Debug)]
+            struct Price(#[component(selector = ".price")] Parsed<f64>);
+
+            #[derive(Component, Debug, PartialEq)]
+            #[component(transparent)]
+            struct Name(String);
+
+            #[derive(Component, Debug, PartialEq)]
+            #[component(transparent)]
+            struct Link(#[component(map = "scraper_component::attribute::href")] String);
+
+            #[derive(Component, Debug)]
+            struct Product(
+                #[component(selector = "a")] Name,
+                #[component(selector = "a")] Link,
+                #[component(selector = ".product")] Price,
+            );
+
+            #[test]
+            fn test_parses() -> Result<()> {
+                super::super::parsed::<Product, _>(HTML, |Product(name, link, Price(Parsed(price)))| {
+                    anyhow::ensure!(name == Name("Anvil".into()));
+                    anyhow::ensure!(link == Link("/anvil".into()));
+                    anyhow::ensure!(price == 12.5);
+                    Ok(())
+                })
+                .flatten()
+            }
+        }
+    }
+
+    pub mod impl_generic {
+        use scraper_component::Component;
+
+        pub mod listing {
+            use {
+                super::*,
+                scraper_component::{
+                    Parsed,
+                    anyhow::{self, Result},
+                },
+                std::str::FromStr,
+            };
+
+            #[rustfmt::skip]
+            const HTML: &str = r#"
+<!DOCTYPE html>
+<body>
+    <ul>
+        <li>1</li>
+        <li>2</li>
+        <li>3</li>
+    </ul>
+    <span class="page">2</span>
+</body>
+"#;
+
+            #[derive(Component, Debug)]
+            struct Listing<Item> {
+                #[component(selector = "li", many)]
+                items: Vec<Item>,
+            }
+
+            #[derive(Component, Debug)]
+            #[component(bound = "T: FromStr + 'document, T::Err: std::error::Error + Send + Sync + 'static")]
+            struct Paginated<T>
+            where
+                T: FromStr,
+            {
+                #[component(selector = "li", many)]
+                items: Vec<Parsed<T>>,
+                #[component(selector = ".page")]
+                page: Parsed<u32>,
+            }
+
+            #[test]
+            fn test_parses() -> Result<()> {
+                super::super::parsed::<Listing<String>, _>(HTML, |listing| {
+                    anyhow::ensure!(listing.items == ["1", "2", "3"]);
+                    Ok(())
+                })
+                .flatten()?;
+                super::super::parsed::<Paginated<u8>, _>(HTML, |paginated| {
+                    anyhow::ensure!(
+                        paginated
+                            .items
+                            .iter()
+                            .map(|Parsed(item)| *item)
+                            .eq([1, 2, 3])
+                    );
+                    anyhow::ensure!(paginated.page.0 == 2);
+                    Ok(())
+                })
+                .flatten()
+            }
+        }
+    }
+
+    pub mod impl_borrowed {
+        use scraper_component::Component;
+
+        pub mod rows {
+            use {
+                super::*,
+                scraper_component::{
+                    TryFromElement,
+                    anyhow::{self, Result},
+                    scraper::Html,
+                },
+                std::borrow::Cow,
+            };
+
+            #[rustfmt::skip]
+            const HTML: &str = r#"
+<!DOCTYPE html>
+<body>
+    <div class="row"><a href="/anvil">Anvil</a><p>Heavy <b>and</b> cheap</p></div>
+</body>
+"#;
+
+            #[derive(Component, Debug)]
+            struct Row<'d> {
+                #[component(selector = "a", map = "scraper_component::attribute::href")]
+                href: &'d str,
+                #[component(selector = "a")]
+                title: Cow<'d, str>,
+                #[component(selector = "p")]
+                description: Cow<'d, str>,
+            }
+
+            #[derive(Component, Debug)]
+            struct SplitText<'d> {
+                #[component(selector = "p")]
+                #[allow(dead_code)]
+                description: &'d str,
+            }
+
+            #[test]
+            fn test_borrows_from_document() -> Result<()> {
+                let html = Html::parse_fragment(HTML);
+                let row = Row::try_from_element(html.root_element())?;
+                anyhow::ensure!(row.href == "/anvil");
+                anyhow::ensure!(matches!(row.title, Cow::Borrowed("Anvil")));
+                anyhow::ensure!(matches!(&row.description, Cow::Owned(description) if description == "Heavy and cheap"));
+                anyhow::ensure!(SplitText::try_from_element(html.root_element()).is_err());
+                Ok(())
+            }
+        }
+
+        pub mod lazy_rows {
+            use {
+                super::*,
+                scraper_component::{
+                    ComponentIter,
+                    Lazy,
+                    Parsed,
+                    TryFromElement,
+                    anyhow::{self, Result},
+                    scraper::Html,
+                },
+            };
+
+            #[rustfmt::skip]
+            const HTML: &str = r#"
+<!DOCTYPE html>
+<body>
+    <table>
+        <tr><td class="name">Anvil</td><td class="price">12</td></tr>
+        <tr><td class="name">Hammer</td><td class="price">7</td></tr>
+        <tr><td class="name">Tongs</td><td class="price">soon</td></tr>
+    </table>
+</body>
+"#;
+
+            #[derive(Component, Debug)]
+            struct Row<'d> {
+                #[component(selector = ".name")]
+                name: &'d str,
+                #[component(selector = ".price")]
+                price: Parsed<u32>,
+            }
+
+            #[derive(Component, Debug)]
+            struct Listing<'d> {
+                #[component(selector = "tr", many)]
+                rows: Lazy<'d, Row<'d>>,
+                #[component(selector = "tr", many)]
+                stream: ComponentIter<'d, Row<'d>>,
+            }
+
+            #[test]
+            fn test_parses_on_iteration() -> Result<()> {
+                let html = Html::parse_fragment(HTML);
+                // the broken row doesn't fail the listing, only iterating up to it does
+                let listing = Listing::try_from_element(html.root_element())?;
+                anyhow::ensure!(listing.rows.len() == 3);
+                let names = listing
+                    .rows
+                    .iter()
+                    .take(2)
+                    .map(|row| row.map(|row| row.name))
+                    .collect::<Result<Vec<_>, _>>()?;
+                anyhow::ensure!(names == ["Anvil", "Hammer"]);
+                anyhow::ensure!(
+                    listing
+                        .rows
+                        .get(1)
+                        .transpose()?
+                        .is_some_and(|row| row.price.0 == 7)
+                );
+                let error = listing
+                    .stream
+                    .last()
+                    .expect("three rows")
+                    .expect_err("price is not a number");
+                anyhow::ensure!(error.path() == "Listing::stream[2]::price", "{error}");
+                anyhow::ensure!(error.html().is_some_and(|html| html.contains("soon")));
+                let error = listing
+                    .rows
+                    .get(2)
+                    .expect("three rows")
+                    .expect_err("price is not a number");
+                anyhow::ensure!(error.path() == "Listing::rows[2]::price", "{error}");
+                Ok(())
+            }
+        }
+    }
+
+    pub mod impl_enum {
+        use scraper_component::Component;
+
+        pub mod listing_cards {
+            use {
+                super::*,
+                scraper_component::{
+                    ComponentError,
+                    ErrorKind,
+                    anyhow::{self, Result},
+                },
+            };
+
+            #[rustfmt::skip]
+            const HTML: &str = r#"
+<!DOCTYPE html>
+<body>
+    <div class="card"><span class="badge">Ad</span><a class="sponsor">ACME</a><h2>Anvil</h2></div>
+    <div class="card"><h2>Rocket skates</h2><span class="price">12</span></div>
+    <div class="card"><h2>Bird seed</h2><span class="sold-out">Sold out</span></div>
+</body>
+"#;
+
+            #[derive(Component, Debug, Clone, PartialEq)]
+            enum Card {
+                Promoted {
+                    #[component(selector = "a.sponsor")]
+                    sponsor: String,
+                    #[component(selector = "h2")]
+                    title: String,
+                },
+                Organic {
+                    #[component(selector = "h2")]
+                    title: String,
+                    #[component(selector = "span.price")]
+                    price: String,
+                },
+                #[allow(dead_code)]
+                SoldOut {
+                    #[component(selector = "span.sold-out")]
+                    badge: String,
+                },
+            }
+
+            #[derive(Component, Debug, Clone, PartialEq)]
+            struct Listing {
+                #[component(selector = "div.card", many)]
+                cards: Vec<Card>,
+            }
+
+            #[test]
+            fn test_first_matching_variant_wins() -> Result<()> {
+                super::super::parsed::<Listing, _>(HTML, |listing| {
+                    anyhow::ensure!(matches!(
+                        listing.cards.as_slice(),
+                        [
+                            Card::Promoted { sponsor, title },
+                            Card::Organic { title: organic, price },
+                            Card::SoldOut { .. },
+                        ] if sponsor == "ACME" && title == "Anvil" && organic == "Rocket skates" && price == "12"
+                    ));
+                    Ok(())
+                })
+                .flatten()
+            }
+
+            #[derive(Component, Debug)]
+            enum OnlyPromoted {
+                #[allow(dead_code)]
+                Promoted {
+                    #[component(selector = "a.sponsor")]
+                    sponsor: String,
+                },
+                #[allow(dead_code)]
+                Featured {
+                    #[component(selector = "span.featured")]
+                    featured: String,
+                },
+            }
+
+            #[test]
+            fn test_reports_every_variant() {
+                let err = super::super::error::<OnlyPromoted>(r#"<div class="card"><h2>Rocket skates</h2></div>"#);
+                assert_eq!(err.kind(), &ErrorKind::NoVariantMatched);
+                assert_eq!(
+                    err.related()
+                        .iter()
+                        .map(ComponentError::path)
+                        .collect::<Vec<_>>(),
+                    ["OnlyPromoted::Promoted::sponsor", "OnlyPromoted::Featured::featured"]
+                );
+            }
+        }
     }
 }
@@ -0,0 +1,5 @@
+/// every diagnostic of the derive macros, `TRYBUILD=overwrite cargo test` regenerates the expected output
+#[test]
+fn compile_fail() {
+    trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
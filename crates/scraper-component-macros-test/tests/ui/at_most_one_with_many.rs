@@ -0,0 +1,9 @@
+use scraper_component::Component;
+
+#[derive(Component)]
+struct Listing {
+    #[component(selector = "li", many, at_most_one)]
+    items: Vec<String>,
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use scraper_component::Captures;
+
+#[derive(Captures)]
+struct Sku(String, String);
+
+fn main() {}
@@ -0,0 +1,9 @@
+use scraper_component::Component;
+
+#[derive(Component)]
+struct Link {
+    #[component(selector = "a", attr = "href", inner_html)]
+    href: String,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use scraper_component::Component;
+
+#[derive(Component)]
+struct Cell {
+    #[component(parent, next_sibling = "td", selector = "span")]
+    value: String,
+}
+
+fn main() {}
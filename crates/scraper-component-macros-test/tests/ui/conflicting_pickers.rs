@@ -0,0 +1,9 @@
+use scraper_component::Component;
+
+#[derive(Component)]
+struct Listing {
+    #[component(selector = "li", first, nth = 2)]
+    item: String,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use scraper_component::Component;
+
+#[derive(Component)]
+struct Product {
+    #[component(fallback = [".price"])]
+    price: String,
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use scraper_component::Component;
+
+#[derive(Component)]
+struct Pricing {
+    #[component(selector = ".amount")]
+    amount: String,
+}
+
+#[derive(Component)]
+struct Product {
+    #[component(flatten, selector = ".pricing")]
+    pricing: Pricing,
+}
+
+fn main() {}
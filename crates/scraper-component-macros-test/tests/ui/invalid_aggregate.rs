@@ -0,0 +1,17 @@
+use scraper_component::Component;
+
+#[derive(Component)]
+struct Product {
+    #[component(selector = ".review", many, attr = "id", count)]
+    reviews: usize,
+    #[component(selector = ".badge", count, exists)]
+    badge: bool,
+    #[component(selector = ".tag", join = ", ")]
+    tags: String,
+    #[component(count)]
+    elements: usize,
+    #[component(exists)]
+    present: bool,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use scraper_component::Component;
+
+#[derive(Component)]
+#[component(bound = "T: +")]
+struct Wrapper<T> {
+    #[component(selector = ".inner")]
+    inner: T,
+}
+
+fn main() {}
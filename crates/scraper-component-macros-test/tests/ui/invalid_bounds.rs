@@ -0,0 +1,11 @@
+use scraper_component::Component;
+
+#[derive(Component)]
+struct Listing {
+    #[component(selector = "li", many, min = 10, max = 2)]
+    items: Vec<String>,
+    #[component(selector = "h1", min = 1)]
+    title: String,
+}
+
+fn main() {}
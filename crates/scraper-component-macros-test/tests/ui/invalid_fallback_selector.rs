@@ -0,0 +1,9 @@
+use scraper_component::Component;
+
+#[derive(Component)]
+struct Product {
+    #[component(selector = ".price-new", fallback = [".price", "li:nth-child("])]
+    price: String,
+}
+
+fn main() {}
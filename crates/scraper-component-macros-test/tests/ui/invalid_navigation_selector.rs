@@ -0,0 +1,9 @@
+use scraper_component::Component;
+
+#[derive(Component)]
+struct Cell {
+    #[component(closest = "tr[", selector = "th")]
+    header: String,
+}
+
+fn main() {}
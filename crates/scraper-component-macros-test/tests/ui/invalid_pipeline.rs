@@ -0,0 +1,15 @@
+use scraper_component::Component;
+
+fn by_len(a: &String, b: &String) -> std::cmp::Ordering {
+    a.len().cmp(&b.len())
+}
+
+#[derive(Component)]
+struct Listing {
+    #[component(selector = "li", sort_by = by_len, limit = 3)]
+    item: String,
+    #[component(selector = "a", many, dedup, dedup_by = String::clone)]
+    links: Vec<String>,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use scraper_component::Component;
+
+#[derive(Component)]
+struct Product {
+    #[component(selector = ".sku", regex = r"SKU: (\w+")]
+    sku: String,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use scraper_component::Component;
+
+#[derive(Component)]
+#[component(root = "article >")]
+struct Product {
+    #[component(selector = ".name")]
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use scraper_component::Component;
+
+fn name(spec: &String) -> String {
+    spec.clone()
+}
+
+#[derive(Component)]
+struct Specs {
+    #[component(selector = "tr", key = name, duplicates = "last")]
+    specs: String,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use scraper_component::Component;
+
+fn shout(element: scraper_component::scraper::ElementRef<'_>) -> scraper_component::anyhow::Result<String> {
+    Ok(element.text().collect::<String>().to_uppercase())
+}
+
+#[derive(Component)]
+struct Link {
+    #[component(selector = "a", map = shout, attr = "href")]
+    href: String,
+}
+
+fn main() {}
@@ -0,0 +1,20 @@
+use scraper_component::Component;
+
+#[derive(Component)]
+#[component(root = "article >")]
+struct Product {
+    #[component(selector = "div[")]
+    name: String,
+    #[component(selector = ".price", regex = "(")]
+    price: String,
+    #[component(selector = "li", first, last)]
+    tag: String,
+}
+
+#[derive(Component)]
+enum Card {
+    Promoted(#[component(selector = "h1[")] String),
+    Plain(#[component(selector = ".name", unknown)] String),
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use scraper_component::Component;
+
+#[derive(Component)]
+#[component(partial)]
+enum Card {
+    Plain(#[component(selector = ".name")] String),
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use scraper_component::Component;
+
+#[derive(Component)]
+#[component(transparent, partial(derive(Debug)))]
+struct Name(String);
+
+fn main() {}
@@ -0,0 +1,11 @@
+use scraper_component::Component;
+
+#[derive(Component)]
+struct Row<'a, 'b> {
+    #[component(selector = ".name")]
+    name: &'a str,
+    #[component(selector = ".value")]
+    value: &'b str,
+}
+
+fn main() {}
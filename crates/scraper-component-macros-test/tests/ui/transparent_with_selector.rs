@@ -0,0 +1,7 @@
+use scraper_component::Component;
+
+#[derive(Component)]
+#[component(transparent)]
+struct Name(#[component(selector = ".name")] String);
+
+fn main() {}
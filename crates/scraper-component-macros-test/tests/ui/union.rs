@@ -0,0 +1,9 @@
+use scraper_component::Component;
+
+#[derive(Component)]
+union Number {
+    int: u32,
+    float: f32,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use scraper_component::Component;
+
+#[derive(Component)]
+struct Product {
+    #[component(selector = ".name", selektor = ".title")]
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,66 @@
+use {
+    super::*,
+    crate::component::{document_lifetime, impl_generics},
+    darling::{FromField, Result},
+};
+
+/// Struct to parse `#[derive(Captures)]` field attributes
+#[derive(FromField, Debug)]
+#[darling(attributes(capture))]
+pub(crate) struct CaptureField {
+    ident: Option<syn::Ident>,
+    /// `#[capture(group = "name")]` - named group the field is read from, defaults to the field name
+    #[darling(default)]
+    group: Option<String>,
+}
+
+pub fn derive_captures_impl(
+    CapturesInput {
+        ident: struct_name,
+        generics,
+        data,
+        bound,
+    }: CapturesInput,
+) -> Result<proc_macro2::TokenStream> {
+    let name = struct_name.to_string();
+    // `supports(struct_named)` guarantees a struct with named fields
+    let fields = data
+        .take_struct()
+        .expect("checked by darling")
+        .fields
+        .into_iter()
+        .map(|CaptureField { ident, group }| {
+            let ident = ident.expect("checked by darling");
+            let field_name = ident.to_string();
+            let group = group.unwrap_or_else(|| field_name.clone());
+            quote! {
+                #ident: ::scraper_component::captured(___text, ___captures.name(#group))
+                    .map_err(|error| error.at_field(#field_name).in_component(#name))?
+            }
+        })
+        .collect::<Vec<_>>();
+    let (document, fresh_lifetime) = document_lifetime(&generics)?;
+    let with_document = impl_generics(
+        &generics,
+        &document,
+        fresh_lifetime,
+        bound.as_ref(),
+        quote!(::scraper_component::TryFromText<#document>),
+    )?;
+    let (impl_generics, _, where_clause) = with_document.split_for_impl();
+    let (_, type_generics, _) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::scraper_component::TryFromCaptures<#document> for #struct_name
+        #type_generics
+        #where_clause {
+            fn try_from_captures(
+                ___text: &::std::borrow::Cow<#document, str>,
+                ___captures: &::scraper_component::regex::Captures<'_>,
+            ) -> ::scraper_component::Result<Self> {
+                Ok(Self {
+                    #(#fields,)*
+                })
+            }
+        }
+    })
+}
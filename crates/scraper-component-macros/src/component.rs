@@ -1,9 +1,17 @@
 use {
     super::*,
     crate::component::struct_field_kind::StructFieldKind,
-    anyhow::{Result, anyhow},
-    darling::FromField,
-    syn::{DataStruct, Path},
+    darling::{
+        Error,
+        FromField,
+        FromMeta,
+        FromVariant,
+        Result,
+        ast::Data,
+        util::{Flag, Override, SpannedValue},
+    },
+    proc_macro2::Span,
+    syn::{Path, spanned::Spanned},
 };
 
 mod struct_field_kind {
@@ -21,6 +29,14 @@ mod struct_field_kind {
                 span: original.span(),
             })
         }
+
+        /// local variable the field is extracted into - tuple fields can't be bound by their index
+        pub fn binding(&self) -> Ident {
+            match self {
+                StructFieldKind::Named(ident) => ident.clone(),
+                StructFieldKind::Index(index) => quote::format_ident!("___field_{}", index.index, span = index.span),
+            }
+        }
     }
 
     impl quote::ToTokens for StructFieldKind {
@@ -45,173 +61,1312 @@ fn default_map_path() -> syn::Path {
     syn::parse_quote!(::scraper_component::try_from_element)
 }
 
+/// compile error pointing at `span`
+pub(crate) fn error_at(span: Span, message: impl std::fmt::Display) -> Error {
+    Error::from(syn::Error::new(span, message))
+}
+
+/// VALIDATE AT COMPILE TIME
+fn validate_selector(selector: &str, span: Span) -> Result<()> {
+    scraper::Selector::parse(selector)
+        .map(|_| ())
+        .map_err(|e| error_at(span, format!("invalid selector '{selector}': {e}")))
+}
+
+/// errors pointing at every option present when at most one of them is allowed
+fn mutually_exclusive(options: &[(&str, Option<Span>)]) -> Result<()> {
+    let present = options
+        .iter()
+        .filter_map(|(name, span)| span.map(|span| (name, span)))
+        .collect::<Vec<_>>();
+    match present.as_slice() {
+        [] | [_] => Ok(()),
+        present => {
+            let names = options
+                .iter()
+                .map(|(name, _)| format!("`{name}`"))
+                .join(", ");
+            present
+                .iter()
+                .map(|(_, span)| error_at(*span, format!("{names} are mutually exclusive")))
+                .collect::<Vec<_>>()
+                .pipe(Error::multiple)
+                .pipe(Err)
+        }
+    }
+}
+
+fn flag_span(flag: &Flag) -> Option<Span> {
+    flag.is_present().then(|| flag.span())
+}
+
+fn value_span<T>(value: &Option<SpannedValue<T>>) -> Option<Span> {
+    value.as_ref().map(SpannedValue::span)
+}
+
+/// `(description, selector of the step, navigation function)`
+type Navigation = (String, Option<SpannedValue<String>>, proc_macro2::TokenStream);
+
 /// Struct to parse field attributes
 #[derive(FromField, Debug)]
 #[darling(attributes(component))]
-struct ComponentField {
+pub(crate) struct ComponentField {
     ident: Option<syn::Ident>,
-    #[allow(dead_code)]
+    vis: syn::Visibility,
     ty: syn::Type,
     #[darling(default)]
-    selector: Option<String>,
-    #[darling(default = "default_map_path")]
-    map: Path,
-    #[darling(default)]
-    many: bool
-    // Field name
-    // #[darling(default)]
-    // skip: bool, // #[bimber(skip)] to skip fields
-}
-
-// #[derive(FromVariant)]
-// #[darling(attributes(bimber))]
-// struct ComponentVariant {
-//     #[allow(dead_code)]
-//     ident: syn::Ident,
-//     fields: darling::ast::Fields<ComponentField>,
-// }
-
-// pub enum OriginalFieldKind {
-//     StructField { field_name: StructFieldKind },
-//     EnumVariant { variant_name: Ident },
-// }
-
-// impl std::fmt::Display for OriginalFieldKind {
-//     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-//         match self {
-//             OriginalFieldKind::StructField { field_name } => field_name.to_string().fmt(f),
-//             OriginalFieldKind::EnumVariant { variant_name } => variant_name.to_string().fmt(f),
-//         }
-//     }
-// }
-
-pub fn derive_component_impl(input: &DeriveInput, ComponentInput { ident: struct_name, generics }: ComponentInput) -> Result<proc_macro2::TokenStream> {
-    match &input.data {
-        syn::Data::Struct(DataStruct { fields, .. }) => fields
+    selector: Option<SpannedValue<String>>,
+    /// `#[component(selector = ".price-new", fallback = [".price", "[itemprop=price]"])]` - tried in order when the selector matches nothing
+    #[darling(default)]
+    fallback: Vec<syn::LitStr>,
+    #[darling(default)]
+    map: Option<Path>,
+    /// `#[component(attr = "href")]` - value of the attribute instead of the element
+    #[darling(default)]
+    attr: Option<SpannedValue<String>>,
+    /// `#[component(inner_html)]` - html of the element's children
+    #[darling(default)]
+    inner_html: Flag,
+    /// `#[component(outer_html)]` - html of the element, including itself
+    #[darling(default)]
+    outer_html: Flag,
+    /// `#[component(own_text)]` - text of the element, excluding text of its child elements
+    #[darling(default)]
+    own_text: Flag,
+    /// `#[component(regex = "...")]` - first capture group of the text (or named groups, see `#[derive(Captures)]`)
+    #[darling(default)]
+    regex: Option<SpannedValue<String>>,
+    #[darling(default)]
+    many: Flag,
+    /// `#[component(skip)]` - field is not extracted at all, it's filled with its `default`
+    #[darling(default)]
+    skip: Flag,
+    /// `#[component(default)]` / `#[component(default = "path::to_fn")]` - used when the selector matches nothing
+    #[darling(default)]
+    default: Option<SpannedValue<Override<Path>>>,
+    /// `#[component(flatten)]` - nested component parsed from the same element, its errors are reported as `Parent::field::nested`
+    #[darling(default)]
+    flatten: Flag,
+    /// `#[component(parent)]` - the selector is evaluated relative to the parent element
+    #[darling(default)]
+    parent: Flag,
+    /// `#[component(closest = "tr")]` - ... relative to the element itself or its nearest ancestor matching the selector
+    #[darling(default)]
+    closest: Option<SpannedValue<String>>,
+    /// `#[component(next_sibling = "dd")]` - ... relative to the nearest following sibling matching the selector
+    #[darling(default)]
+    next_sibling: Option<SpannedValue<String>>,
+    /// `#[component(prev_sibling = "h2")]` - ... relative to the nearest preceding sibling matching the selector
+    #[darling(default)]
+    prev_sibling: Option<SpannedValue<String>>,
+    /// `#[component(first)]` - only the first matching element is used, the rest is ignored
+    #[darling(default)]
+    first: Flag,
+    /// `#[component(last)]` - only the last matching element is used
+    #[darling(default)]
+    last: Flag,
+    /// `#[component(nth = 2)]` - only the matching element at given (zero-based) index is used
+    #[darling(default)]
+    nth: Option<SpannedValue<usize>>,
+    /// `#[component(at_most_one)]` - `Option<T>` field which errors when more than one element matches
+    #[darling(default)]
+    at_most_one: Flag,
+    /// `#[component(many, key = path::to_key_fn)]` - map field, every value is stored under `to_key_fn(&value)`
+    #[darling(default)]
+    key: Option<Path>,
+    /// `#[component(many, duplicates = "first")]` - what a map field does with a repeated key
+    #[darling(default)]
+    duplicates: Option<SpannedValue<DuplicateKeys>>,
+    /// `#[component(many, min = 2)]` - fewer matching elements are an error, elements are counted once `filter` is applied
+    /// and before anything is extracted, so `filter_value`, `dedup` or `limit` don't change the count (`skip_errors` does)
+    #[darling(default)]
+    min: Option<SpannedValue<usize>>,
+    /// `#[component(many, max = 50)]` - more matching elements are an error
+    #[darling(default)]
+    max: Option<SpannedValue<usize>>,
+    /// `#[component(many, skip_errors)]` - items which fail to parse are dropped (and reported), not the whole field
+    #[darling(default)]
+    skip_errors: Flag,
+    /// `#[component(filter = path::to_pred)]` - only elements for which `pred(&element)` holds are extracted
+    #[darling(default)]
+    filter: Option<Path>,
+    /// `#[component(many, filter_value = path::to_pred)]` - only values for which `pred(&value)` holds are collected
+    #[darling(default)]
+    filter_value: Option<Path>,
+    /// `#[component(many, dedup)]` - values equal to an earlier one are dropped
+    #[darling(default)]
+    dedup: Flag,
+    /// `#[component(many, dedup_by = path::to_key_fn)]` - values with the same `key_fn(&value)` as an earlier one are dropped
+    #[darling(default)]
+    dedup_by: Option<Path>,
+    /// `#[component(many, sort_by = path::to_compare_fn)]` - values are (stably) sorted with `compare_fn(&a, &b)`
+    #[darling(default)]
+    sort_by: Option<Path>,
+    /// `#[component(many, limit = 10)]` - at most the first N values (after filtering, sorting and deduplication) are collected
+    #[darling(default)]
+    limit: Option<SpannedValue<usize>>,
+    /// `#[component(selector = ".review", count)] reviews: usize` - number of selected elements
+    #[darling(default)]
+    count: Flag,
+    /// `#[component(selector = ".sold-out", exists)] sold_out: bool` - whether any element is selected
+    #[darling(default)]
+    exists: Flag,
+    /// `#[component(selector = ".tag", many, join = ", ")] tags: String` - the values joined with the separator
+    #[darling(default)]
+    join: Option<SpannedValue<String>>,
+}
+
+/// `duplicates = "error" | "first" | "last"`, see `scraper_component::DuplicateKeys`
+#[derive(FromMeta, Debug, Clone, Copy)]
+#[darling(rename_all = "snake_case")]
+pub(crate) enum DuplicateKeys {
+    Error,
+    First,
+    Last,
+}
+
+impl ToTokens for DuplicateKeys {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let variant = quote::format_ident!("{self:?}");
+        tokens.extend(quote!(::scraper_component::DuplicateKeys::#variant));
+    }
+}
+
+impl ComponentField {
+    /// options which decide which elements are selected, as opposed to how they are turned into values
+    fn selection_spans(&self) -> Vec<(&'static str, Option<Span>)> {
+        let Self {
+            selector,
+            fallback,
+            many,
+            default,
+            parent,
+            closest,
+            next_sibling,
+            prev_sibling,
+            first,
+            last,
+            nth,
+            at_most_one,
+            key,
+            duplicates,
+            min,
+            max,
+            skip_errors,
+            filter,
+            filter_value,
+            dedup,
+            dedup_by,
+            sort_by,
+            limit,
+            count,
+            exists,
+            join,
+            ..
+        } = self;
+        vec![
+            ("selector", value_span(selector)),
+            ("fallback", fallback.first().map(|fallback| fallback.span())),
+            ("many", flag_span(many)),
+            ("default", value_span(default)),
+            ("parent", flag_span(parent)),
+            ("closest", value_span(closest)),
+            ("next_sibling", value_span(next_sibling)),
+            ("prev_sibling", value_span(prev_sibling)),
+            ("first", flag_span(first)),
+            ("last", flag_span(last)),
+            ("nth", value_span(nth)),
+            ("at_most_one", flag_span(at_most_one)),
+            ("key", key.as_ref().map(|key| key.span())),
+            ("duplicates", value_span(duplicates)),
+            ("min", value_span(min)),
+            ("max", value_span(max)),
+            ("skip_errors", flag_span(skip_errors)),
+            ("filter", filter.as_ref().map(|filter| filter.span())),
+            ("filter_value", filter_value.as_ref().map(|filter| filter.span())),
+            ("dedup", flag_span(dedup)),
+            ("dedup_by", dedup_by.as_ref().map(|dedup_by| dedup_by.span())),
+            ("sort_by", sort_by.as_ref().map(|sort_by| sort_by.span())),
+            ("limit", value_span(limit)),
+            ("count", flag_span(count)),
+            ("exists", flag_span(exists)),
+            ("join", value_span(join)),
+        ]
+    }
+
+    /// options which decide how the element is turned into a value
+    fn extractor_spans(&self) -> Vec<(&'static str, Option<Span>)> {
+        let Self {
+            map,
+            attr,
+            inner_html,
+            outer_html,
+            own_text,
+            regex,
+            ..
+        } = self;
+        vec![
+            ("map", map.as_ref().map(|map| map.span())),
+            ("attr", value_span(attr)),
+            ("inner_html", flag_span(inner_html)),
+            ("outer_html", flag_span(outer_html)),
+            ("own_text", flag_span(own_text)),
+            ("regex", value_span(regex)),
+        ]
+    }
+
+    /// errors pointing at every option in `options` used on a field which can't use any of them
+    fn reject(&self, options: Vec<(&'static str, Option<Span>)>, reason: &str) -> Result<()> {
+        let mut errors = Error::accumulator();
+        options
+            .into_iter()
+            .filter_map(|(name, span)| span.map(|span| (name, span)))
+            .for_each(|(name, span)| errors.push(error_at(span, format!("`{name}` can't be used here: {reason}"))));
+        errors.finish()
+    }
+
+    /// selectors (primary, then fallbacks) matched by the single-pass plan of the component - every selector evaluated
+    /// relative to the component's element, fields which navigate elsewhere first walk on their own
+    fn planned_selectors(&self) -> Vec<String> {
+        match self.skip.is_present() || self.flatten.is_present() || self.navigates() {
+            true => Vec::new(),
+            false => self.selectors(),
+        }
+    }
+
+    /// selectors of fields which navigate first (the navigation step's, then primary and fallbacks), held by the
+    /// `___SELECTORS` static of the component
+    fn shared_selectors(&self) -> Vec<String> {
+        let Self {
+            closest,
+            next_sibling,
+            prev_sibling,
+            ..
+        } = self;
+        match self.navigates() {
+            true => [closest, next_sibling, prev_sibling]
+                .into_iter()
+                .flatten()
+                .map(|selector| selector.to_string())
+                .chain(self.selectors())
+                .collect(),
+            false => Vec::new(),
+        }
+    }
+
+    /// primary selector, then fallbacks
+    fn selectors(&self) -> Vec<String> {
+        self.selector
             .iter()
-            .map(|f| {
-                ComponentField::from_field(f)
-                    .for_anyhow()
-                    .with_context(|| format!("parsing field: {f:?}"))
+            .map(|selector| selector.to_string())
+            .chain(self.fallback.iter().map(syn::LitStr::value))
+            .collect()
+    }
+
+    fn navigates(&self) -> bool {
+        let Self {
+            parent,
+            closest,
+            next_sibling,
+            prev_sibling,
+            ..
+        } = self;
+        parent.is_present() || closest.is_some() || next_sibling.is_some() || prev_sibling.is_some()
+    }
+
+    /// where the field's selector is evaluated from
+    fn navigation(&self) -> Result<Option<Navigation>> {
+        let Self {
+            parent,
+            closest,
+            next_sibling,
+            prev_sibling,
+            ..
+        } = self;
+        mutually_exclusive(&[
+            ("parent", flag_span(parent)),
+            ("closest", value_span(closest)),
+            ("next_sibling", value_span(next_sibling)),
+            ("prev_sibling", value_span(prev_sibling)),
+        ])?;
+        let with_selector = |step: &str, selector: &SpannedValue<String>| {
+            let function = quote::format_ident!("{step}");
+            (
+                format!("{step}({})", selector.as_str()),
+                Some(selector.clone()),
+                quote!(::scraper_component::navigate::#function),
+            )
+        };
+        [
+            parent
+                .is_present()
+                .then(|| ("parent".to_string(), None, quote!(::scraper_component::navigate::parent))),
+            closest
+                .as_ref()
+                .map(|selector| with_selector("closest", selector)),
+            next_sibling
+                .as_ref()
+                .map(|selector| with_selector("next_sibling", selector)),
+            prev_sibling
+                .as_ref()
+                .map(|selector| with_selector("prev_sibling", selector)),
+        ]
+        .into_iter()
+        .flatten()
+        .next()
+        .pipe(Ok)
+    }
+
+    /// narrows the matched elements down to a single one (`first`, `last`, `nth`)
+    fn picker(&self) -> Result<Option<proc_macro2::TokenStream>> {
+        let Self { first, last, nth, .. } = self;
+        mutually_exclusive(&[("first", flag_span(first)), ("last", flag_span(last)), ("nth", value_span(nth))])?;
+        [
+            first
+                .is_present()
+                .then(|| quote!(let select = select.take(1);)),
+            last.is_present()
+                .then(|| quote!(let select = select.last().into_iter();)),
+            nth.as_ref().map(|nth| {
+                let nth = **nth;
+                quote!(let select = select.skip(#nth).take(1);)
+            }),
+        ]
+        .into_iter()
+        .flatten()
+        .next()
+        .pipe(Ok)
+    }
+
+    /// clean-ups of the values of `many` fields, applied in order: `filter_value`, `sort_by`, `dedup` / `dedup_by`, `limit`.
+    /// Sorting and deduplication need every value, so the first failing item fails the field before they run.
+    fn pipeline(&self) -> Result<Option<proc_macro2::TokenStream>> {
+        let Self {
+            many,
+            filter_value,
+            dedup,
+            dedup_by,
+            sort_by,
+            limit,
+            ..
+        } = self;
+        mutually_exclusive(&[("dedup", flag_span(dedup)), ("dedup_by", dedup_by.as_ref().map(|dedup_by| dedup_by.span()))])?;
+        if !many.is_present() {
+            self.reject(
+                vec![
+                    ("filter_value", filter_value.as_ref().map(|filter| filter.span())),
+                    ("dedup", flag_span(dedup)),
+                    ("dedup_by", dedup_by.as_ref().map(|dedup_by| dedup_by.span())),
+                    ("sort_by", sort_by.as_ref().map(|sort_by| sort_by.span())),
+                    ("limit", value_span(limit)),
+                ],
+                "only `many` fields have values to clean up",
+            )?;
+        }
+        let filter_value = filter_value.as_ref().map(|filter| {
+            quote! {
+                let mapped = mapped.filter(|item| match item {
+                    ::std::result::Result::Ok(value) => #filter(value),
+                    ::std::result::Result::Err(_) => true,
+                });
+            }
+        });
+        let sort = sort_by.as_ref().map(|compare| {
+            quote! {
+                let mut items = items;
+                items.sort_by(#compare);
+            }
+        });
+        let dedup = [
+            dedup
+                .is_present()
+                .then(|| quote!(let items = ::scraper_component::pipeline::dedup(items);)),
+            dedup_by
+                .as_ref()
+                .map(|key| quote!(let items = ::scraper_component::pipeline::dedup_by_key(items, #key);)),
+        ]
+        .into_iter()
+        .flatten()
+        .next();
+        let limit = limit.as_deref().map(|limit| quote!(.take(#limit)));
+        let reorder = match sort.is_some() || dedup.is_some() {
+            true => Some(quote! {
+                let items = mapped.collect::<::std::result::Result<::std::vec::Vec<_>, ::scraper_component::ComponentError>>()?;
+                #sort
+                #dedup
+                let mapped = items
+                    .into_iter()
+                    #limit
+                    .map(::std::result::Result::<_, ::scraper_component::ComponentError>::Ok);
+            }),
+            false => limit.map(|limit| quote!(let mapped = mapped #limit;)),
+        };
+        Ok((filter_value.is_some() || reorder.is_some()).then(|| quote!(#filter_value #reorder)))
+    }
+
+    /// expression producing the field out of all the selected elements instead of collecting them - `count` and `exists`
+    /// only look at the elements (nothing is extracted), `join` concatenates the `String` values of a `many` field
+    fn aggregate(&self) -> Result<Option<proc_macro2::TokenStream>> {
+        let Self {
+            many,
+            count,
+            exists,
+            join,
+            key,
+            duplicates,
+            ..
+        } = self;
+        mutually_exclusive(&[("count", flag_span(count)), ("exists", flag_span(exists)), ("join", value_span(join))])?;
+        if count.is_present() || exists.is_present() {
+            const SELECTION: &[&str] = &[
+                "selector",
+                "fallback",
+                "parent",
+                "closest",
+                "next_sibling",
+                "prev_sibling",
+                "first",
+                "last",
+                "nth",
+                "filter",
+                "count",
+                "exists",
+            ];
+            self.reject(
+                self.selection_spans()
+                    .into_iter()
+                    .chain(self.extractor_spans())
+                    .filter(|(name, _)| !SELECTION.contains(name))
+                    .collect(),
+                "`count` and `exists` only look at which elements are selected",
+            )?;
+            if self.selector.is_none() && !self.navigates() {
+                return Err(error_at(
+                    flag_span(count)
+                        .or(flag_span(exists))
+                        .expect("checked above"),
+                    "`count` and `exists` require a `selector` or a navigation, the element itself is always there",
+                ));
+            }
+        }
+        if join.is_some() {
+            match many.is_present() {
+                true => self.reject(
+                    vec![("key", key.as_ref().map(|key| key.span())), ("duplicates", value_span(duplicates))],
+                    "joined values are not collected",
+                )?,
+                false => self.reject(vec![("join", value_span(join))], "only `many` fields have values to join")?,
+            }
+        }
+        let ok = quote!(::std::result::Result::<_, ::scraper_component::ComponentError>::Ok);
+        [
+            count.is_present().then(|| quote!(#ok(select.count()))),
+            exists.is_present().then(|| {
+                quote! {{
+                    let mut select = select;
+                    #ok(select.next().is_some())
+                }}
+            }),
+            join.as_ref().map(|separator| {
+                let separator = separator.as_str();
+                quote! {
+                    mapped
+                        .collect::<::std::result::Result<::std::vec::Vec<::std::string::String>, ::scraper_component::ComponentError>>()
+                        .map(|values| values.join(#separator))
+                }
+            }),
+        ]
+        .into_iter()
+        .flatten()
+        .next()
+        .pipe(Ok)
+    }
+
+    /// function turning each selected element into the field's item - `map`, or one of the text sources
+    /// (`attr`, `inner_html`, `outer_html`, `own_text`, element text by default) optionally narrowed down by a `regex`
+    fn extractor(&self) -> Result<proc_macro2::TokenStream> {
+        let Self {
+            map,
+            attr,
+            inner_html,
+            outer_html,
+            own_text,
+            regex,
+            ..
+        } = self;
+        mutually_exclusive(&[
+            ("attr", value_span(attr)),
+            ("inner_html", flag_span(inner_html)),
+            ("outer_html", flag_span(outer_html)),
+            ("own_text", flag_span(own_text)),
+        ])?;
+        if let Some(map) = map {
+            self.reject(
+                self.extractor_spans()
+                    .into_iter()
+                    .filter(|(name, _)| *name != "map")
+                    .collect(),
+                "`map` takes care of the extraction on its own",
+            )?;
+            return Ok(quote!(#map));
+        }
+        let text_source = [
+            attr.as_ref().map(|attr| {
+                let attr = attr.as_str();
+                quote!(|element| ::scraper_component::attribute::attr(element, #attr))
+            }),
+            inner_html
+                .is_present()
+                .then(|| quote!(::scraper_component::extract::inner_html)),
+            outer_html
+                .is_present()
+                .then(|| quote!(::scraper_component::extract::outer_html)),
+            own_text
+                .is_present()
+                .then(|| quote!(::scraper_component::extract::own_text)),
+        ]
+        .into_iter()
+        .flatten()
+        .next();
+        match (text_source, regex) {
+            (Some(source), None) => Ok(source),
+            (None, None) => Ok(default_map_path().to_token_stream()),
+            (source, Some(regex)) => {
+                let span = regex.span();
+                let regex = regex.as_str();
+                // VALIDATE AT COMPILE TIME
+                regex::Regex::new(regex).map_err(|e| error_at(span, format!("invalid regex: {e}")))?;
+                let source = source.unwrap_or_else(|| quote!(|element| Ok(::scraper_component::text(element))));
+                Ok(quote! {{
+                    static REGEX: ::std::sync::LazyLock<::scraper_component::regex::Regex> = ::std::sync::LazyLock::new(|| {
+                        ::scraper_component::regex::Regex::new(#regex).expect("validated at compile time")
+                    });
+                    |element| (#source)(element).and_then(|text| ::scraper_component::extract::regex(text, &REGEX))
+                }})
+            }
+        }
+    }
+}
+
+/// Options of `#[component(partial(...))]`
+#[derive(FromMeta, Debug, Default, Clone)]
+pub(crate) struct PartialOptions {
+    /// derives of the generated `{Component}Partial` struct
+    #[darling(default)]
+    derive: darling::util::PathList,
+}
+
+/// Struct to parse enum variant attributes
+#[derive(FromVariant, Debug)]
+#[darling(attributes(component))]
+pub(crate) struct ComponentVariant {
+    ident: syn::Ident,
+    fields: darling::ast::Fields<ComponentField>,
+}
+
+/// generates an expression extracting the field as `Result<T>`, errors get the field prepended to their path.
+/// Fields with selectors in the `plan` take their elements from the `___matches` of the component,
+/// the others refer to their interned selectors in the `shared` `___SELECTORS` static by index.
+fn extract_field(
+    component: &str,
+    plan: &[String],
+    shared: &[String],
+    kind: &StructFieldKind,
+    field @ ComponentField {
+        ty,
+        selector,
+        many,
+        skip,
+        default,
+        flatten,
+        at_most_one,
+        fallback,
+        key,
+        duplicates,
+        min,
+        max,
+        skip_errors,
+        ..
+    }: &ComponentField,
+) -> Result<proc_macro2::TokenStream> {
+    let field_name = kind.to_string();
+    let default = default.as_ref().map(|default| match &**default {
+        Override::Inherit => quote!(::std::default::Default::default()),
+        Override::Explicit(path) => quote!(#path()),
+    });
+    if skip.is_present() {
+        field.reject(
+            field
+                .selection_spans()
+                .into_iter()
+                .filter(|(name, _)| *name != "default")
+                .chain(field.extractor_spans())
+                .chain([("flatten", flag_span(flatten))])
+                .collect(),
+            "skipped fields are not extracted",
+        )?;
+        return default
+            .unwrap_or_else(|| quote!(::std::default::Default::default()))
+            .pipe(|default| Ok(quote!(::std::result::Result::<_, ::scraper_component::ComponentError>::Ok(#default))));
+    }
+    if flatten.is_present() {
+        field.reject(
+            field
+                .selection_spans()
+                .into_iter()
+                .chain(field.extractor_spans())
+                .collect(),
+            "flattened fields are parsed from the same element",
+        )?;
+        return Ok(quote! {
+            <#ty as ::scraper_component::TryFromElement<'_>>::try_from_element(___element).map_err(|error| error.at_field(#field_name))
+        });
+    }
+    let mut errors = Error::accumulator();
+    if let (None, Some(fallback)) = (selector, fallback.first()) {
+        errors.push(error_at(fallback.span(), "`fallback` selectors require a `selector`"));
+    }
+    if let (Some(many), Some(at_most_one)) = (flag_span(many), flag_span(at_most_one)) {
+        [many, at_most_one]
+            .into_iter()
+            .for_each(|span| errors.push(error_at(span, "`at_most_one` can't be combined with `many`")));
+    }
+    if !many.is_present() {
+        errors.handle(field.reject(
+            vec![("key", key.as_ref().map(|key| key.span())), ("duplicates", value_span(duplicates))],
+            "only `many` fields are collected into maps and sets",
+        ));
+        errors.handle(field.reject(
+            vec![("min", value_span(min)), ("max", value_span(max))],
+            "only `many` fields have a count to bound",
+        ));
+        errors.handle(field.reject(vec![("skip_errors", flag_span(skip_errors))], "only items of `many` fields can be skipped"));
+    }
+    if let (Some(min), Some(max)) = (min, max)
+        && **min > **max
+    {
+        errors.push(error_at(min.span(), format!("`min` can't be greater than `max` ({} > {})", **min, **max)));
+    }
+    let map = errors.handle(field.extractor());
+    let navigation = errors.handle(field.navigation()).flatten();
+    let picker = errors.handle(field.picker()).flatten();
+    let pipeline = errors.handle(field.pipeline()).flatten();
+    let aggregate = errors.handle(field.aggregate()).flatten();
+    // elements are filtered before anything is picked out of them
+    let picker = field
+        .filter
+        .as_ref()
+        .map(|filter| quote!(let select = select.filter(|element| #filter(element));))
+        .into_iter()
+        .chain(picker)
+        .collect::<proc_macro2::TokenStream>();
+
+    // primary selector first, fallbacks are tried in order only when it matches nothing
+    let selectors = selector
+        .iter()
+        .map(|selector| (selector.to_string(), selector.span()))
+        .chain(
+            fallback
+                .iter()
+                .map(|fallback| (fallback.value(), fallback.span())),
+        )
+        .collect::<Vec<_>>();
+    selectors.iter().for_each(|(selector, span)| {
+        errors
+            .handle(validate_selector(selector, *span))
+            .unwrap_or_default()
+    });
+    let shared_selector = |selector: &str| {
+        let idx = shared
+            .iter()
+            .position(|shared| shared == selector)
+            .expect("selector is shared");
+        quote!(___SELECTORS[#idx])
+    };
+    let (navigation_str, scope) = match navigation {
+        Some((description, Some(selector), function)) => {
+            errors.handle(validate_selector(&selector, selector.span()));
+            let selector = shared_selector(selector.as_str());
+            (Some(description), quote!(#function(___element, #selector)))
+        }
+        Some((description, None, function)) => (Some(description), quote!(#function(___element))),
+        None => (None, quote!(::std::option::Option::Some(___element))),
+    };
+    errors.finish()?;
+    let map = map.expect("errors handled above");
+    // values are keyed once cleaned up, so the pipeline options see the values themselves
+    let pipeline = key
+        .as_ref()
+        .map(|key| quote!(let mapped = mapped.map(|item| item.map(|value| (#key(&value), value)));))
+        .pipe(|keyed| quote!(#pipeline #keyed));
+
+    let selectors = selectors
+        .into_iter()
+        .map(|(selector, _)| selector)
+        .collect::<Vec<_>>();
+
+    let selector_str = match selectors.as_slice() {
+        [] => "(no-selector)".to_string(),
+        [selector] => selector.clone(),
+        [selector, fallbacks @ ..] => format!("{selector}, then fallbacks: {}", fallbacks.join(", ")),
+    }
+    .pipe(|selector_str| match navigation_str {
+        Some(navigation) => format!("{navigation} {selector_str}"),
+        None => selector_str,
+    });
+    let or_default = default.map(|default| {
+        quote! {
+            let mut select = select.peekable();
+            if select.peek().is_none() {
+                return Ok(#default);
+            }
+        }
+    });
+
+    // items parsed on their own learn where they are, in case they keep their failure (`Vec<Result<Row>>`)
+    let map = match field
+        .extractor_spans()
+        .into_iter()
+        .all(|(_, span)| span.is_none())
+    {
+        true if many.is_present() => quote! {
+            |element| ::scraper_component::try_from_item(
+                element,
+                ::scraper_component::FieldContext {
+                    component: #component,
+                    field: #field_name,
+                    selector: #selector_str,
+                    element: ___element,
+                },
+                index,
+            )
+        },
+        _ => map,
+    };
+    let bounded = min.is_some() || max.is_some();
+    // items of `many` fields are attributed to their index (`Page::items[3]::price`)
+    let map_items = match (many.is_present(), skip_errors.is_present()) {
+        (true, true) => {
+            let count_skipped = bounded.then(|| quote!(.or_else(|| { skipped.set(skipped.get() + 1); ::std::option::Option::None })));
+            quote! {
+            let mapped = select
+                .enumerate()
+                .filter_map(|(index, element)| {
+                    let item = (#map)(element).map_err(::scraper_component::ComponentError::from);
+                    ::scraper_component::skip_failed(#field_name, index, element, item)#count_skipped
+                })
+                .map(::std::result::Result::<_, ::scraper_component::ComponentError>::Ok);
+            }
+        }
+        (true, false) => quote! {
+            let mapped = select
+                .enumerate()
+                .map(|(index, element)| (#map)(element).map_err(|error| ::scraper_component::ComponentError::from(error).at_index(index)));
+        },
+        (false, _) => quote! {
+            let mapped = select.map(#map).map(|item| item.map_err(::scraper_component::ComponentError::from));
+        },
+    };
+    let perform_parse = match (many.is_present(), at_most_one.is_present()) {
+        (_, true) => quote!(::scraper_component::try_collect_at_most_one(mapped)),
+        (true, false) if key.is_some() || duplicates.is_some() => {
+            let duplicates = duplicates
+                .as_deref()
+                .copied()
+                .unwrap_or(DuplicateKeys::Error);
+            quote!(<#ty as ::scraper_component::TryCollectDistinct<_>>::try_collect_distinct(mapped, #duplicates))
+        }
+        (true, false) => quote!(<#ty as ::scraper_component::TryCollectFrom<_>>::try_collect(mapped)),
+        (false, false) => quote!(<[#ty; 1] as ::scraper_component::TryCollectFrom<_>>::try_collect(mapped).map(|[v]| v)),
+    };
+    // `count` and `exists` don't extract anything out of the elements
+    let map_items = (!field.count.is_present() && !field.exists.is_present()).then_some(map_items);
+    let perform_parse = aggregate.unwrap_or(perform_parse);
+    // the elements left by `filter` are counted before they're extracted, even those the pipeline or a set or map drops,
+    // except the items `skip_errors` drops - the ones left over once the collection is done are only counted, never parsed
+    let (counted, perform_parse) = match (min.as_deref(), max.as_deref()) {
+        (None, None) => (None, perform_parse),
+        (min, max) => {
+            let min = min.copied().unwrap_or_default();
+            let max = match max {
+                Some(max) => quote!(::std::option::Option::Some(#max)),
+                None => quote!(::std::option::Option::None),
+            };
+            let (skipped, uncount_skipped) = match skip_errors.is_present() {
+                true => (Some(quote!(let skipped = ::std::cell::Cell::new(0);)), Some(quote!(.skipping(skipped.get())))),
+                false => (None, None),
+            };
+            let counted = quote! {
+                let mut counted = ::scraper_component::Counted::new(select);
+                let select = &mut counted;
+                #skipped
+            };
+            let perform_parse = quote! {{
+                let collected = #perform_parse;
+                counted #uncount_skipped.check(collected, ::scraper_component::Count { min: #min, max: #max })
+            }};
+            (Some(counted), perform_parse)
+        }
+    };
+    let planned = field.planned_selectors();
+    if !planned.is_empty() {
+        let indices = planned.iter().map(|selector| {
+            plan.iter()
+                .position(|planned| planned == selector)
+                .expect("selector is in the plan")
+        });
+        return Ok(quote! {
+            (|| {
+                let select = ___matches.first_matching(&[#(#indices),*]);
+                #picker
+                #counted
+                #or_default
+                #map_items
+                #pipeline
+                #perform_parse
+            })()
+            .map_err(|error: ::scraper_component::ComponentError| error.at_field(#field_name).located(#selector_str, ___element))
+        });
+    }
+    // without selectors the field is the element navigated to (or the component's own element)
+    let select = match selectors.is_empty() {
+        true => quote!(#scope.into_iter()),
+        false => {
+            let selectors = selectors.iter().map(|selector| shared_selector(selector));
+            quote! {
+                #scope
+                    .into_iter()
+                    .flat_map(|scope| ::scraper_component::select_first_matching(scope, &[#(#selectors),*]))
+            }
+        }
+    };
+    Ok(quote! {
+        (|| {
+            let select = #select;
+            #picker
+            #counted
+            #or_default
+            #map_items
+            #pipeline
+            #perform_parse
+        })()
+        .map_err(|error: ::scraper_component::ComponentError| error.at_field(#field_name).located(#selector_str, ___element))
+    })
+}
+
+/// generates an extracting expression for every field, macro errors of all the fields are reported together.
+/// The statements coming first match every distinct selector of the fields in a single walk over the element (`___matches`)
+/// and keep the remaining selectors in a `static` - every selector is interned by `scraper_component::selector`, so each
+/// distinct string is parsed once for the whole program, whichever components use it.
+fn extract_fields(
+    component_ident: &syn::Ident,
+    fields: &[ComponentField],
+) -> Result<(proc_macro2::TokenStream, Vec<(StructFieldKind, proc_macro2::TokenStream)>)> {
+    let plan = fields
+        .iter()
+        .flat_map(ComponentField::planned_selectors)
+        .unique()
+        .collect::<Vec<_>>();
+    let shared = fields
+        .iter()
+        .flat_map(ComponentField::shared_selectors)
+        .unique()
+        .collect::<Vec<_>>();
+    let match_plan = (!plan.is_empty()).then(|| {
+        let size = plan.len();
+        quote! {
+            static ___PLAN: ::std::sync::LazyLock<::scraper_component::plan::Plan<#size>> =
+                ::std::sync::LazyLock::new(|| ::scraper_component::plan::Plan::new([#(#plan),*]));
+            let ___matches = ::scraper_component::plan::match_all(___element, &___PLAN);
+        }
+    });
+    let shared_selectors = (!shared.is_empty()).then(|| {
+        let size = shared.len();
+        quote! {
+            static ___SELECTORS: ::std::sync::LazyLock<[&::scraper_component::scraper::Selector; #size]> =
+                ::std::sync::LazyLock::new(|| [#(::scraper_component::selector(#shared)),*]);
+        }
+    });
+    let match_plan = quote!(#shared_selectors #match_plan);
+    let mut errors = Error::accumulator();
+    fields
+        .iter()
+        .enumerate()
+        .map(|(idx, component_field @ ComponentField { ident, .. })| {
+            ident
+                .as_ref()
+                .map(|i| StructFieldKind::Named(i.clone()))
+                .unwrap_or(StructFieldKind::index(component_ident, idx))
+                .pipe(|field_kind| (field_kind, component_field))
+        })
+        .filter_map(|(kind, field)| {
+            errors
+                .handle(extract_field(&component_ident.to_string(), &plan, &shared, &kind, field))
+                .map(|extract| (kind, extract))
+        })
+        .collect::<Vec<_>>()
+        .pipe(|fields| errors.finish_with((quote!(#match_plan), fields)))
+}
+
+/// extracts all the fields and constructs `constructor` (`Self` or `Self::Variant`) out of them,
+/// tuple structs and variants are constructed with braces as well (`Self { 0: ___field_0 }`).
+/// The first failing field is returned, unless `accumulate_errors` is set - then every field is extracted and all the failures are returned together
+fn construct(
+    component_ident: &syn::Ident,
+    constructor: proc_macro2::TokenStream,
+    fields: &[ComponentField],
+    accumulate_errors: bool,
+) -> Result<proc_macro2::TokenStream> {
+    extract_fields(component_ident, fields).map(|(match_plan, fields)| {
+        let field_names = fields.iter().map(|(f, _)| f).collect::<Vec<_>>();
+        let field_bindings = fields.iter().map(|(f, _)| f.binding()).collect::<Vec<_>>();
+        let field_impls = fields.iter().map(|(_, f)| f);
+        match accumulate_errors {
+            false => quote! {
+                #match_plan
+                #(let #field_bindings = #field_impls?;)*
+
+                Ok(#constructor {
+                    #(#field_names: #field_bindings,)*
+                })
+            },
+            true => accumulate(&field_bindings, field_impls, quote!(#constructor { #(#field_names: #field_bindings,)* }))
+                .pipe(|accumulate| quote!(#match_plan #accumulate)),
+        }
+    })
+}
+
+/// binds every field result as an `Option`, `constructor` is evaluated only when none of them failed - otherwise all the failures are returned
+fn accumulate(
+    field_bindings: &[syn::Ident],
+    field_results: impl Iterator<Item = impl ToTokens>,
+    constructor: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    quote! {
+        let mut ___field_errors = ::std::vec::Vec::new();
+        #(let #field_bindings = (#field_results).map_err(|error| ___field_errors.push(error)).ok();)*
+
+        match (#(#field_bindings,)*) {
+            (#(Some(#field_bindings),)*) => Ok(#constructor),
+            _ => Err(::scraper_component::ComponentError::multiple(___field_errors)),
+        }
+    }
+}
+
+/// `#[component(partial)]` - `{Component}Partial` struct mirroring the component with every field wrapped in a `Result`,
+/// the `PartialComponent` impl producing it and `complete()` turning it back into the component
+fn partial(
+    struct_name: &syn::Ident,
+    vis: &syn::Visibility,
+    generics: &syn::Generics,
+    (document, with_document): (&syn::Lifetime, &syn::Generics),
+    fields: &darling::ast::Fields<ComponentField>,
+    PartialOptions { derive }: &PartialOptions,
+    narrow_to_root: Option<&proc_macro2::TokenStream>,
+) -> Result<proc_macro2::TokenStream> {
+    let name = struct_name.to_string();
+    let derive = (!derive.is_empty()).then(|| {
+        let derive = derive.iter();
+        quote!(#[derive(#(#derive),*)])
+    });
+    let partial_name = quote::format_ident!("{}Partial", struct_name);
+    let (match_plan, extracted) = extract_fields(struct_name, &fields.fields)?;
+    let field_names = extracted.iter().map(|(f, _)| f).collect::<Vec<_>>();
+    let field_bindings = extracted
+        .iter()
+        .map(|(f, _)| f.binding())
+        .collect::<Vec<_>>();
+    let field_impls = extracted.iter().map(|(_, f)| f);
+    let (partial_generics, type_generics, where_clause) = generics.split_for_impl();
+    let (impl_generics, _, impl_where_clause) = with_document.split_for_impl();
+    let declared_fields = fields
+        .fields
+        .iter()
+        .map(|ComponentField { ident, vis, ty, .. }| {
+            let ident = ident.as_ref().map(|ident| quote!(#ident:));
+            quote!(#vis #ident ::scraper_component::Result<#ty>)
+        });
+    let declaration = match fields.style {
+        darling::ast::Style::Tuple => quote! {
+            #vis struct #partial_name #generics (#(#declared_fields,)*) #where_clause;
+        },
+        darling::ast::Style::Struct | darling::ast::Style::Unit => quote! {
+            #vis struct #partial_name #generics #where_clause {
+                #(#declared_fields,)*
+            }
+        },
+    };
+    let complete = accumulate(
+        &field_bindings,
+        field_names.iter().map(|field| quote!(self.#field)),
+        quote!(#struct_name { #(#field_names: #field_bindings,)* }),
+    );
+    Ok(quote! {
+        #[doc = concat!("Every field of [`", #name, "`] as a `Result`, see `#[component(partial)]`")]
+        #derive
+        #declaration
+
+        impl #partial_generics #partial_name #type_generics #where_clause {
+            #[doc = concat!("[`", #name, "`] when none of the fields failed, all the failures otherwise")]
+            #vis fn complete(self) -> ::scraper_component::Result<#struct_name #type_generics> {
+                #complete
+            }
+        }
+
+        impl #impl_generics ::scraper_component::PartialComponent<#document> for #struct_name #type_generics #impl_where_clause {
+            type Partial = #partial_name #type_generics;
+
+            fn try_from_element_partial(___element: ::scraper_component::scraper::ElementRef<#document>)
+                ->
+            ::scraper_component::Result<Self::Partial> {
+                #narrow_to_root
+                #match_plan
+                Ok(#partial_name {
+                    #(#field_names: (#field_impls).map_err(|error| error.in_component(#name)),)*
+                })
+            }
+        }
+    })
+}
+
+/// `#[component(transparent)]` - the only field is parsed (or extracted) straight from the element, as if the wrapper wasn't there
+fn transparent(component_ident: &syn::Ident, fields: &[ComponentField]) -> Result<proc_macro2::TokenStream> {
+    match fields {
+        [field @ ComponentField { ident, skip, flatten, .. }] => {
+            field.reject(
+                field
+                    .selection_spans()
+                    .into_iter()
+                    .chain([("skip", flag_span(skip)), ("flatten", flag_span(flatten))])
+                    .collect(),
+                "transparent components can only customize how their field is extracted (`map`, `attr`, `regex`...), not which elements are selected",
+            )?;
+            let map = field.extractor()?;
+            let member = ident
+                .as_ref()
+                .map(|i| StructFieldKind::Named(i.clone()))
+                .unwrap_or(StructFieldKind::index(component_ident, 0));
+            Ok(quote! {
+                (#map)(___element)
+                    .map(|inner| Self { #member: inner })
+                    .map_err(::scraper_component::ComponentError::from)
             })
-            .collect::<Result<Vec<_>>>()
-            .context("collecting fields")
-            .map(|fields| {
-                fields
-                    .iter()
-                    .enumerate()
-                    .map(
-                        |(
-                            idx,
-                            component_field @ ComponentField {
-                                ident,
-                                ty: _,
-                                selector: _,
-                                map: _,
-                                many: _
-                            },
-                        )| {
-                            ident
-                                .as_ref()
-                                .map(|i| StructFieldKind::Named(i.clone()))
-                                .unwrap_or(StructFieldKind::index(&input.ident, idx))
-                                .pipe(|field_kind| (field_kind, component_field))
-                        },
-                    )
-                    .map(
-                        |(
-                            kind,
-                            ComponentField {
-                                ident: _,
-                                ty,
-                                selector,
-                                map,
-                                many
-                            },
-                        )| {
-                            let selector = selector.as_ref();
-
-                            let selector_str = selector.map(|s| s.to_string());
-                            let field_name = kind.to_string();
-                            let struct_name = struct_name.to_string();
-                            // VALIDATE AT COMPILE TIME
-                            let _selector = selector
-                                .map(|selector| scraper::Selector::parse(selector))
-                                .transpose()
-                                .map_err(|e| anyhow::anyhow!("{e:?}"))
-                                .with_context(|| format!("invalid selector for field '{field_name}': '{selector:?}'"))
-                                .unwrap();
-
-                            let define_selector = match selector {
-                                Some(selector) => {
-                                    quote! {
-                                        Some(::scraper_component::scraper::Selector::parse(#selector).expect("validated at compile time"))
-                                    }
-                                }
-                                None => quote! {
-                                    None
-                                },
-                            };
-                            let selector_str = selector_str.unwrap_or_else(|| "(no-selector)".into());
-
-                            let perform_parse = match many {
-                                true => quote! {
-                                    <#ty as ::scraper_component::TryCollectFrom<_>>::try_collect(mapped)
-                                        .with_context(|| format!("{}\n\nreading {}::{} (selector: {})", ___element.html(), #struct_name, #field_name, #selector_str))
-                                    
-                                },
-                                false => quote! {
-                                    <[#ty; 1] as ::scraper_component::TryCollectFrom<_>>::try_collect(mapped)
-                                        .with_context(|| format!("{}\n\nreading {}::{} (selector: {})", ___element.html(), #struct_name, #field_name, #selector_str))
-                                        .map(|[v]| v)
-                                },
-                            };
-                            (
-                                kind.clone(),
-                                quote::quote! {
-                                    let #kind = {
-                                        use ::scraper_component::{anyhow::{Result, Context, anyhow}, scraper::Selector};
-                                        thread_local! {
-                                            static SELECTOR: Option<::scraper_component::scraper::Selector> =
-                                                #define_selector;    
-                                        }
-                                        SELECTOR.with(|selector| {
-                                            let select = selector.as_ref().map(|selector| {
-                                                (Box::new(___element.select(selector)) as Box<dyn Iterator<Item = _>>)
-                                            })
-                                            .unwrap_or_else(|| Box::new(std::iter::once(___element)));
-                                            let mapped = select.map(#map);
-                                            #perform_parse    
-                                        })
-                                    }?;
-                                },
-                            )
-                        },
-                    )
-                    .collect::<Vec<_>>()
-                    .pipe(|fields| {
-                        let field_impls = fields.iter().map(|(_, f)| f);
-                        let (_impl_generics, type_generics, where_clause) = generics.split_for_impl();
-                        let field_names = fields.iter().map(|(f, _)| f);
-                        quote! {
-                            impl <'document> ::scraper_component::TryFromElement<'document> for #struct_name
-                            #type_generics
-                            #where_clause{
-                                fn try_from_element(___element: ::scraper_component::scraper::ElementRef<'document>)
-                                    ->
-                                ::scraper_component::anyhow::Result<Self> {
-                                    #(#field_impls)*
-
-                                    Ok(Self {
-                                        #(#field_names,)*
-                                    })
+        }
+        _ => Err(error_at(
+            component_ident.span(),
+            format!("transparent components must have exactly one field, found {}", fields.len()),
+        )),
+    }
+}
+
+/// lifetime the component borrows from the document with - a component with a single lifetime parameter (`Row<'d>`)
+/// is parsed from `ElementRef<'d>`, components without lifetimes get a fresh `'document` one
+pub(crate) fn document_lifetime(generics: &syn::Generics) -> Result<(syn::Lifetime, bool)> {
+    match generics.lifetimes().collect::<Vec<_>>().as_slice() {
+        [] => Ok((syn::parse_quote!('document), true)),
+        [syn::LifetimeParam { lifetime, .. }] => Ok((lifetime.clone(), false)),
+        [_, extra @ ..] => extra
+            .iter()
+            .map(|syn::LifetimeParam { lifetime, .. }| {
+                error_at(
+                    lifetime.span(),
+                    "components borrow from a single document, so at most one lifetime parameter is supported",
+                )
+            })
+            .collect::<Vec<_>>()
+            .pipe(Error::multiple)
+            .pipe(Err),
+    }
+}
+
+/// generics of the component with the document lifetime prepended (unless the component already has it), every type parameter
+/// is required to implement `inferred` (`TryFromElement` for components) unless `#[component(bound = "...")]` replaces the inferred bounds
+pub(crate) fn impl_generics(
+    generics: &syn::Generics,
+    document: &syn::Lifetime,
+    fresh_lifetime: bool,
+    bound: Option<&SpannedValue<String>>,
+    inferred: proc_macro2::TokenStream,
+) -> Result<syn::Generics> {
+    let bounds = match bound {
+        Some(bound) => syn::parse::Parser::parse_str(syn::punctuated::Punctuated::<syn::WherePredicate, syn::Token![,]>::parse_terminated, bound)
+            .map_err(|e| error_at(bound.span(), format!("invalid bound: {e}")))?
+            .into_iter()
+            .collect::<Vec<_>>(),
+        None => generics
+            .type_params()
+            .map(|syn::TypeParam { ident, .. }| syn::parse_quote!(#ident: #inferred))
+            .collect(),
+    };
+    generics
+        .clone()
+        .tap_mut(|generics| {
+            if fresh_lifetime {
+                generics.params.insert(0, syn::parse_quote!(#document));
+            }
+            generics.make_where_clause().predicates.extend(bounds);
+        })
+        .pipe(Ok)
+}
+
+/// `#[component(root = "...")]` - rebinds `___element` to the root of the component before anything is extracted
+fn narrow_to_root(root: Option<&SpannedValue<String>>) -> Result<Option<proc_macro2::TokenStream>> {
+    root.map(|root| {
+        validate_selector(root, root.span())?;
+        let root = root.as_str();
+        Ok(quote! {
+            static ___ROOT: ::std::sync::LazyLock<&::scraper_component::scraper::Selector> =
+                ::std::sync::LazyLock::new(|| ::scraper_component::selector(#root));
+            let ___element = ::scraper_component::root(___element, *___ROOT).map_err(|error| error.located(#root, ___element))?;
+        })
+    })
+    .transpose()
+}
+
+/// darling gives up on the whole input as soon as one of its fields doesn't parse - the fields it could parse on their own
+/// are still checked (selectors, regexes, conflicting options...) variant by variant, so every mistake is reported at once
+pub(crate) fn check_parsed_fields(input: &syn::DeriveInput, error: Error) -> Error {
+    let variants = match &input.data {
+        syn::Data::Struct(data) => vec![&data.fields],
+        syn::Data::Enum(data) => data
+            .variants
+            .iter()
+            .map(|variant| &variant.fields)
+            .collect(),
+        syn::Data::Union(_) => vec![],
+    };
+    let mut errors = Error::accumulator();
+    errors.push(error);
+    variants.into_iter().for_each(|fields| {
+        let parsed = fields
+            .iter()
+            .filter_map(|field| ComponentField::from_field(field).ok())
+            .collect::<Vec<_>>();
+        errors.handle(extract_fields(&input.ident, &parsed));
+    });
+    errors.finish().expect_err("darling's error is pushed")
+}
+
+pub fn derive_component_impl(
+    ComponentInput {
+        ident: struct_name,
+        generics,
+        data,
+        vis,
+        transparent: is_transparent,
+        bound,
+        root,
+        accumulate_errors,
+        partial: partial_options,
+    }: ComponentInput,
+) -> Result<proc_macro2::TokenStream> {
+    let name = struct_name.to_string();
+    let mut errors = Error::accumulator();
+    let narrow_to_root = errors.handle(narrow_to_root(root.as_ref())).flatten();
+    let partial_fields = match (&data, &partial_options) {
+        (_, None) => None,
+        (Data::Struct(_), Some(options)) if is_transparent.is_present() => {
+            errors.push(error_at(options.span(), "transparent components can't be partial"));
+            None
+        }
+        (Data::Struct(fields), Some(options)) => Some((fields, (**options).clone().unwrap_or_default())),
+        (Data::Enum(_), Some(options)) => {
+            errors.push(error_at(options.span(), "only structs can be partial"));
+            None
+        }
+    };
+    let body = match &data {
+        Data::Struct(fields) if is_transparent.is_present() => transparent(&struct_name, &fields.fields),
+        Data::Enum(_) if is_transparent.is_present() => Err(error_at(is_transparent.span(), "enums can't be transparent")),
+        Data::Struct(fields) => construct(&struct_name, quote!(Self), &fields.fields, accumulate_errors.is_present()),
+        // variants are tried in declaration order, first one to parse wins
+        Data::Enum(variants) => {
+            let mut variant_errors = Error::accumulator();
+            variants
+                .iter()
+                .filter_map(|ComponentVariant { ident, fields }| {
+                    let variant_name = ident.to_string();
+                    variant_errors
+                        .handle(construct(&struct_name, quote!(Self::#ident), &fields.fields, accumulate_errors.is_present()))
+                        .map(|construct| {
+                            quote! {
+                                match (|| -> ::scraper_component::Result<Self> { #construct })() {
+                                    Ok(variant) => return Ok(variant),
+                                    Err(reason) => ___errors.push(reason.at_field(#variant_name)),
                                 }
                             }
-                        }
-                    })
-            }),
-        syn::Data::Enum(_data_enum) => Err(anyhow!("enums not supported")),
-        syn::Data::Union(_data_union) => Err(anyhow!("unions are not supported")),
-    }
-    .context("deriving component")
+                        })
+                })
+                .collect::<Vec<_>>()
+                .pipe(|variants| variant_errors.finish_with(variants))
+                .map(|variants| {
+                    quote! {
+                        let mut ___errors = ::std::vec::Vec::new();
+                        #(#variants)*
+                        Err(::scraper_component::ComponentError::new(::scraper_component::ErrorKind::NoVariantMatched).with_related(___errors))
+                    }
+                })
+        }
+    };
+    let body = errors.handle(body);
+    let generics_with_document = errors.handle(document_lifetime(&generics).and_then(|(document, fresh_lifetime)| {
+        impl_generics(
+            &generics,
+            &document,
+            fresh_lifetime,
+            bound.as_ref(),
+            quote!(::scraper_component::TryFromElement<#document>),
+        )
+        .map(|with_document| (document, with_document))
+    }));
+    let partial = generics_with_document
+        .as_ref()
+        .and_then(|(document, with_document)| {
+            partial_fields.and_then(|(fields, options)| {
+                errors.handle(partial(
+                    &struct_name,
+                    &vis,
+                    &generics,
+                    (document, with_document),
+                    fields,
+                    &options,
+                    narrow_to_root
+                        .as_ref()
+                        .map(|narrow_to_root| {
+                            quote! {
+                                let ___element = (|| -> ::scraper_component::Result<_> {
+                                    #narrow_to_root
+                                    Ok(___element)
+                                })()
+                                .map_err(|error| error.in_component(#name))?;
+                            }
+                        })
+                        .as_ref(),
+                ))
+            })
+        });
+    errors.finish()?;
+    let (body, (document, with_document)) = body
+        .zip(generics_with_document)
+        .expect("errors handled above");
+    let (impl_generics, _, where_clause) = with_document.split_for_impl();
+    let (_, type_generics, _) = generics.split_for_impl();
+    Ok(quote! {
+        #partial
+
+        impl #impl_generics ::scraper_component::TryFromElement<#document> for #struct_name
+        #type_generics
+        #where_clause{
+            fn try_from_element(___element: ::scraper_component::scraper::ElementRef<#document>)
+                ->
+            ::scraper_component::Result<Self> {
+                (|| -> ::scraper_component::Result<Self> {
+                    #narrow_to_root
+                    #body
+                })()
+                // nested components are renamed by the component they are nested in
+                .map_err(|error| error.in_component(#name))
+            }
+        }
+    })
 }
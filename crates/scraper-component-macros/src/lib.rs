@@ -1,30 +1,65 @@
 use {
-    anyhow::{Context, Result},
-    darling::FromDeriveInput,
+    darling::{
+        FromDeriveInput,
+        util::{Flag, SpannedValue},
+    },
+    itertools::Itertools,
     proc_macro::{self, TokenStream},
-    quote::quote,
+    quote::{ToTokens, quote},
     syn::{DeriveInput, parse_macro_input},
     tap::{Pipe, Tap, TapFallible},
 };
 
+mod captures;
 mod component;
 
-trait AnyhowExt<T> {
-    fn for_anyhow(self) -> Result<T>;
-}
-
-impl<T> AnyhowExt<T> for darling::Result<T> {
-    fn for_anyhow(self) -> Result<T> {
-        self.map_err(|e| anyhow::anyhow!("DARLING:\n{e:?}"))
-    }
-}
-
 // Struct to parse derive input attributes
-#[derive(FromDeriveInput, Clone, Debug)]
-#[darling(attributes(component), supports(struct_named))]
+#[derive(FromDeriveInput, Debug)]
+#[darling(attributes(component), supports(struct_any, enum_any))]
 struct ComponentInput {
     ident: syn::Ident,
+    vis: syn::Visibility,
     generics: syn::Generics,
+    data: darling::ast::Data<component::ComponentVariant, component::ComponentField>,
+    /// `#[component(transparent)]` - newtype delegating to its only field
+    #[darling(default)]
+    transparent: Flag,
+    /// `#[component(bound = "T: ...")]` - replaces the `T: TryFromElement<'document>` bounds inferred for type parameters
+    #[darling(default)]
+    bound: Option<SpannedValue<String>>,
+    /// `#[component(root = "article.product")]` - the element itself or its only descendant matching the selector
+    /// is the one the fields are extracted from
+    #[darling(default)]
+    root: Option<SpannedValue<String>>,
+    /// `#[component(accumulate_errors)]` - every field is extracted even when some of them fail, all the failures are returned together
+    #[darling(default)]
+    accumulate_errors: Flag,
+    /// `#[component(partial)]` / `#[component(partial(derive(Debug)))]` - generates a `{Component}Partial` companion
+    /// holding every field as a `Result`, so whatever parsed can be used even when some fields failed
+    #[darling(default)]
+    partial: Option<SpannedValue<darling::util::Override<component::PartialOptions>>>,
+}
+
+// Struct to parse `#[derive(Captures)]` input attributes
+#[derive(FromDeriveInput, Debug)]
+#[darling(attributes(capture), supports(struct_named))]
+struct CapturesInput {
+    ident: syn::Ident,
+    generics: syn::Generics,
+    data: darling::ast::Data<(), captures::CaptureField>,
+    /// `#[capture(bound = "T: ...")]` - replaces the `T: TryFromText<'document>` bounds inferred for type parameters
+    #[darling(default)]
+    bound: Option<SpannedValue<String>>,
+}
+
+/// Maps named capture groups of `#[component(regex = "...")]` onto struct fields
+#[proc_macro_derive(Captures, attributes(capture))]
+pub fn captures_macro(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    CapturesInput::from_derive_input(&input)
+        .and_then(captures::derive_captures_impl)
+        .unwrap_or_else(darling::Error::write_errors)
+        .pipe(TokenStream::from)
 }
 
 #[proc_macro_derive(Component, attributes(component))]
@@ -50,14 +85,15 @@ pub fn component_macro(input: TokenStream) -> TokenStream {
         });
         parse_macro_input!(input as DeriveInput)
     }
-    .pipe_ref(|input| {
-        ComponentInput::from_derive_input(&input.clone())
-            .for_anyhow()
-            .map(|component| (input, component))
+    .pipe_ref(|input| match &input.data {
+        // darling's `supports` check runs after it's already given up on unions
+        syn::Data::Union(union) => Err(darling::Error::from(syn::Error::new(
+            union.union_token.span,
+            "unions are not supported, only structs and enums",
+        ))),
+        _ => ComponentInput::from_derive_input(input).map_err(|error| component::check_parsed_fields(input, error)),
     })
-    .context("parsing input")
-    .and_then(|(input, component_input)| component::derive_component_impl(input, component_input))
-    .with_context(|| format!("parsing:\n{input}"))
+    .and_then(component::derive_component_impl)
     .tap_ok_dbg(
         #[allow(unused_variables)]
         {
@@ -75,9 +111,9 @@ pub fn component_macro(input: TokenStream) -> TokenStream {
             }
         },
     )
-    .tap_err(|err| eprintln!("ERROR:\n{err:?}"))
-    .map(TokenStream::from)
-    .unwrap_or_else(|e| panic!("scraper-component proc macro failed\nreason\n{e:?}"))
+    // every problem becomes a `compile_error!` pointing at the offending attribute
+    .unwrap_or_else(darling::Error::write_errors)
+    .pipe(TokenStream::from)
 }
 
 mod utils;
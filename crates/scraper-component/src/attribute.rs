@@ -1,11 +1,15 @@
-use {anyhow::{Context, Result}, scraper::ElementRef};
+use {
+    crate::{ComponentError, ErrorKind, Result, TryFromText},
+    scraper::ElementRef,
+    std::borrow::Cow,
+};
 
 macro_rules! attributes {
 	($(#[$meta:meta] $key:ident $html:literal),* $(,)?) => {
         paste::paste! {
             $(
                 #[$meta]
-                pub fn [<$key _opt>] (el: ElementRef<'_>) -> Result<Option<String>>
+                pub fn [<$key _opt>]<'document, T: TryFromText<'document>> (el: ElementRef<'document>) -> Result<Option<T>>
 
                 {
                     self::attr_opt(el, $html)
@@ -15,15 +19,14 @@ macro_rules! attributes {
                 pub fn [<$key _static>]<const VALUE: &'static str> (el: ElementRef<'_>) -> Result<crate::Hardcoded<VALUE>>
 
                 {
-                    self::attr(el, $html).and_then(|value| match value == VALUE {
+                    self::attr::<&str>(el, $html).and_then(|value| match value == VALUE {
                         true => Ok(crate::Hardcoded),
-                        false => Err(anyhow::anyhow!("expected {VALUE}, found {value}"))
+                        false => Err(ComponentError::new(ErrorKind::HardcodedMismatch { expected: VALUE, found: value.to_owned() })),
                     })
-                    .with_context(|| format!("when validating hardcoded {}", stringify!($key)))
                 }
 
                 #[$meta]
-                pub fn $key (el: ElementRef<'_>) -> Result<String>
+                pub fn $key<'document, T: TryFromText<'document>> (el: ElementRef<'document>) -> Result<T>
 
                 {
                     self::attr(el, $html)
@@ -33,12 +36,18 @@ macro_rules! attributes {
     }
 }
 
-pub fn attr_opt(el: ElementRef<'_>, name: &str) -> Result<Option<String>> {
-    Ok(el.attr(name).map(ToOwned::to_owned))
+/// attribute values are borrowed from the document, so `&str` and `Cow<str>` don't allocate
+pub fn attr_opt<'document, T: TryFromText<'document>>(el: ElementRef<'document>, name: &str) -> Result<Option<T>> {
+    el.attr(name)
+        .map(|value| T::try_from_text(Cow::Borrowed(value)))
+        .transpose()
 }
 
-pub fn attr(el: ElementRef<'_>, name: &str) -> Result<String> {
-    el.attr(name).with_context(|| format!("no attribute {name} on:\n{el}", el=el.html())).map(ToOwned::to_owned)
+pub fn attr<'document, T: TryFromText<'document>>(el: ElementRef<'document>, name: &str) -> Result<T> {
+    match el.attr(name) {
+        Some(value) => T::try_from_text(Cow::Borrowed(value)),
+        None => T::try_from_missing_text(&format!("attribute {name:?}")),
+    }
 }
 
 attributes! {
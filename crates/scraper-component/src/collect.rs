@@ -0,0 +1,252 @@
+//! Keyed collections for `many` fields - maps are collected from `(K, V)` items, or from values keyed with
+//! `#[component(many, key = path::to_key_fn)]`, sets from the items themselves.
+//! Repeated keys are handled according to `DuplicateKeys`.
+//! Any collection can also be bounded to a number of elements, `#[component(many, min = 2, max = 50)]` or `Bounded<C, MIN, MAX>`,
+//! or keep going past failing items with `Tolerant<C>`.
+use {
+    crate::{ComponentError, Count, ErrorKind, Result, TryCollectFrom},
+    std::{
+        collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+        fmt::Debug,
+        hash::{BuildHasher, Hash},
+        ops::{Deref, DerefMut},
+    },
+};
+
+/// What to do when a key (or set element) repeats, `#[component(many, duplicates = "first")]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DuplicateKeys {
+    /// fail with `ErrorKind::DuplicateKey`, the default
+    #[default]
+    Error,
+    /// keep the value of the first item with the key
+    First,
+    /// keep the value of the last item with the key (collections which keep insertion order keep the first position)
+    Last,
+}
+
+/// Collections storing every key once - maps collected from `(key, value)` items and sets,
+/// plain `TryCollectFrom` rejects duplicate keys
+pub trait TryCollectDistinct<T>: Sized {
+    fn try_collect_distinct<I>(from: I, duplicates: DuplicateKeys) -> Result<Self>
+    where
+        I: Iterator<Item = Result<T>>;
+}
+
+macro_rules! impl_distinct_collect {
+    (
+        $ty:ty, $item_ty:ty, [$($bounds:tt)*],
+        |$collection:ident, $item:ident| contains: $contains:expr, key: $key:expr, insert: $insert:expr
+    ) => {
+        impl<$($bounds)*> TryCollectDistinct<$item_ty> for $ty {
+            fn try_collect_distinct<I>(from: I, duplicates: DuplicateKeys) -> Result<Self>
+            where
+                I: Iterator<Item = Result<$item_ty>>,
+            {
+                from.enumerate().try_fold(Self::default(), |mut $collection, (index, item)| {
+                    let $item = item?;
+                    match ($contains, duplicates) {
+                        (true, DuplicateKeys::Error) => {
+                            Err(ComponentError::new(ErrorKind::DuplicateKey { key: format!("{:?}", $key) }).at_index(index))
+                        }
+                        (true, DuplicateKeys::First) => Ok($collection),
+                        (false, _) | (true, DuplicateKeys::Last) => {
+                            $insert;
+                            Ok($collection)
+                        }
+                    }
+                })
+            }
+        }
+
+        impl<$($bounds)*> TryCollectFrom<$item_ty> for $ty {
+            fn try_collect<I>(from: I) -> Result<Self>
+            where
+                I: Iterator<Item = Result<$item_ty>>,
+            {
+                Self::try_collect_distinct(from, DuplicateKeys::Error)
+            }
+        }
+    };
+}
+
+macro_rules! impl_map_collect {
+    ($ty:ty, [$($bounds:tt)*]) => {
+        impl_distinct_collect!(
+            $ty, (K, V), [$($bounds)*],
+            |map, item| contains: map.contains_key(&item.0), key: item.0, insert: map.insert(item.0, item.1)
+        );
+    };
+}
+
+/// `Last` replaces the stored element with the equal one found later
+macro_rules! impl_set_collect {
+    ($ty:ty, [$($bounds:tt)*]) => {
+        impl_distinct_collect!(
+            $ty, T, [$($bounds)*],
+            |set, item| contains: set.contains(&item), key: item, insert: set.replace(item)
+        );
+    };
+}
+
+impl_map_collect!(HashMap<K, V, S>, [K: Eq + Hash + Debug, V, S: BuildHasher + Default]);
+impl_map_collect!(BTreeMap<K, V>, [K: Ord + Debug, V]);
+#[cfg(feature = "indexmap")]
+impl_map_collect!(indexmap::IndexMap<K, V, S>, [K: Eq + Hash + Debug, V, S: BuildHasher + Default]);
+
+impl_set_collect!(HashSet<T, S>, [T: Eq + Hash + Debug, S: BuildHasher + Default]);
+impl_set_collect!(BTreeSet<T>, [T: Ord + Debug]);
+#[cfg(feature = "indexmap")]
+impl_set_collect!(indexmap::IndexSet<T, S>, [T: Eq + Hash + Debug, S: BuildHasher + Default]);
+
+/// Iterator adapter counting the elements that went through it, so the collection can be checked against
+/// the expected count afterwards (sets and maps may hold fewer elements than matched) - it wraps the matched elements
+/// rather than the parsed items, whatever the collection left over is counted without being parsed
+pub struct Counted<I> {
+    inner: I,
+    count: usize,
+}
+
+impl<I> Counted<I>
+where
+    I: Iterator,
+{
+    pub fn new(inner: I) -> Self {
+        Self { inner, count: 0 }
+    }
+
+    /// `skipped` of the counted elements failed to parse and were dropped (`skip_errors`), they don't count
+    pub fn skipping(mut self, skipped: usize) -> Self {
+        self.count -= skipped;
+        self
+    }
+
+    /// the `collected` collection, unless the number of elements (collected or not) is outside of `expected`
+    pub fn check<C>(mut self, collected: Result<C>, expected: Count) -> Result<C> {
+        let collected = collected?;
+        let found = self.count + self.inner.by_ref().count();
+        match (found < expected.min, expected.max.is_some_and(|max| found > max)) {
+            (true, _) => Err(ComponentError::missing(expected, found)),
+            (_, true) => Err(ComponentError::too_many(expected, found)),
+            (false, false) => Ok(collected),
+        }
+    }
+}
+
+impl<I> Iterator for Counted<I>
+where
+    I: Iterator,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().inspect(|_| self.count += 1)
+    }
+}
+
+/// Collection `C` of at least `MIN` and at most `MAX` elements, `usize::MAX` leaves it unbounded:
+/// `Bounded<Vec<Product>, 2, 50>` fails with "expected 2..=50, found 0".
+/// Bounds which can't be met (`MIN > MAX`) fail to build:
+///
+/// ```compile_fail,E0080
+/// use scraper_component::{Bounded, TryCollectFrom};
+///
+/// let _ = Bounded::<Vec<u8>, 5, 2>::try_collect(std::iter::empty());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Bounded<C, const MIN: usize, const MAX: usize>(pub C);
+
+impl<C, const MIN: usize, const MAX: usize> Bounded<C, MIN, MAX> {
+    pub const EXPECTED: Count = {
+        assert!(MIN <= MAX, "`Bounded<C, MIN, MAX>` needs MIN <= MAX");
+        Count {
+            min: MIN,
+            max: match MAX {
+                usize::MAX => None,
+                max => Some(max),
+            },
+        }
+    };
+
+    pub fn into_inner(self) -> C {
+        self.0
+    }
+}
+
+impl<T, C, const MIN: usize, const MAX: usize> TryCollectFrom<T> for Bounded<C, MIN, MAX>
+where
+    C: TryCollectFrom<T>,
+{
+    fn try_collect<I>(from: I) -> Result<Self>
+    where
+        I: Iterator<Item = Result<T>>,
+    {
+        let mut counted = Counted::new(from);
+        let collected = C::try_collect(&mut counted);
+        counted.check(collected, Self::EXPECTED).map(Self)
+    }
+}
+
+impl<C, const MIN: usize, const MAX: usize> Deref for Bounded<C, MIN, MAX> {
+    type Target = C;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<C, const MIN: usize, const MAX: usize> DerefMut for Bounded<C, MIN, MAX> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Collection `C` of the items which parsed, along with the errors of the ones which didn't - like
+/// `#[component(many, skip_errors)]`, but the caller gets the failures (each one located at its item, `Listing::rows[1]::price`,
+/// with the html of the item) instead of `tracing` warnings. `Tolerant<Bounded<Vec<Row>, 3, 50>>` only counts the parsed items.
+#[derive(Debug)]
+pub struct Tolerant<C> {
+    pub items: C,
+    pub skipped: Vec<ComponentError>,
+}
+
+impl<C> Tolerant<C> {
+    pub fn into_inner(self) -> C {
+        self.items
+    }
+}
+
+impl<T, C> TryCollectFrom<Result<T>> for Tolerant<C>
+where
+    C: TryCollectFrom<T>,
+{
+    fn try_collect<I>(from: I) -> Result<Self>
+    where
+        I: Iterator<Item = Result<Result<T>>>,
+    {
+        let mut skipped = Vec::new();
+        let items = C::try_collect(from.filter_map(|item| match item {
+            Ok(Err(error)) => {
+                skipped.push(error);
+                None
+            }
+            Ok(Ok(item)) => Some(Ok(item)),
+            Err(error) => Some(Err(error)),
+        }))?;
+        Ok(Self { items, skipped })
+    }
+}
+
+impl<C> Deref for Tolerant<C> {
+    type Target = C;
+
+    fn deref(&self) -> &Self::Target {
+        &self.items
+    }
+}
+
+impl<C> DerefMut for Tolerant<C> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.items
+    }
+}
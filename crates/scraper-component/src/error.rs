@@ -0,0 +1,277 @@
+//! Structured errors returned by every extraction, so breakages can be told apart programmatically
+//! (`err.kind()`, `err.path()`, `err.selector()`) instead of matching on error messages.
+use {
+    itertools::Itertools,
+    scraper::ElementRef,
+    std::{borrow::Cow, fmt},
+};
+
+pub type Result<T, E = ComponentError> = std::result::Result<T, E>;
+
+/// How many elements were expected to match
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Count {
+    pub min: usize,
+    /// `None` when there is no upper limit
+    pub max: Option<usize>,
+}
+
+impl Count {
+    pub const fn exactly(count: usize) -> Self {
+        Self { min: count, max: Some(count) }
+    }
+
+    pub const fn at_least(min: usize) -> Self {
+        Self { min, max: None }
+    }
+
+    pub const fn at_most(max: usize) -> Self {
+        Self { min: 0, max: Some(max) }
+    }
+}
+
+impl fmt::Display for Count {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.min, self.max) {
+            (min, Some(max)) if min == max => write!(f, "{min}"),
+            (min, None) => write!(f, "at least {min}"),
+            (0, Some(max)) => write!(f, "at most {max}"),
+            (min, Some(max)) => write!(f, "{min}..={max}"),
+        }
+    }
+}
+
+/// What went wrong, the part of the error monitoring should group on
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// fewer elements matched than required
+    Missing { expected: Count, found: usize },
+    /// more elements matched than allowed
+    TooMany { expected: Count, found: usize },
+    /// the element is there, but the value isn't (an attribute, a regex capture group...)
+    MissingValue { what: String },
+    /// text couldn't be turned into the value (`Parsed<T>`, a `regex` that didn't match...)
+    Parse { text: String, expected: Cow<'static, str> },
+    /// a keyed `many` field found the key (`Debug`-formatted) again
+    DuplicateKey { key: String },
+    /// `Hardcoded<VALUE>` found a different value
+    HardcodedMismatch { expected: &'static str, found: String },
+    /// none of the enum variants could be parsed, see `ComponentError::related` for why
+    NoVariantMatched,
+    /// error returned by a custom `map` function
+    Custom,
+    /// several fields of a `#[component(accumulate_errors)]` component failed, see `ComponentError::related`
+    Multiple,
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorKind::Missing { expected, found } | ErrorKind::TooMany { expected, found } => {
+                write!(f, "expected {expected}, found {found}")
+            }
+            ErrorKind::MissingValue { what } => write!(f, "{what} is missing"),
+            ErrorKind::Parse { text, expected } => write!(f, "could not parse {text:?} into {expected}"),
+            ErrorKind::DuplicateKey { key } => write!(f, "duplicate key {key}"),
+            ErrorKind::HardcodedMismatch { expected, found } => write!(f, "expected {expected:?}, found {found:?}"),
+            ErrorKind::NoVariantMatched => write!(f, "no variant matched"),
+            ErrorKind::Custom => write!(f, "custom extraction failed"),
+            ErrorKind::Multiple => write!(f, "multiple fields failed"),
+        }
+    }
+}
+
+/// Step of the path leading to the failing field
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    /// field, or enum variant
+    Field(String),
+    /// index of the item within a `many` field
+    Index(usize),
+}
+
+impl fmt::Display for PathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathSegment::Field(field) => write!(f, "::{field}"),
+            PathSegment::Index(index) => write!(f, "[{index}]"),
+        }
+    }
+}
+
+/// Error of any extraction - the path is built up on the way out, every component prepending the field the error came from
+/// (`Page::items[3]::price`), while the selector and html are those of the innermost field
+#[derive(Debug)]
+pub struct ComponentError(Box<Inner>);
+
+/// boxed so that results stay small on the happy path
+#[derive(Debug)]
+struct Inner {
+    kind: ErrorKind,
+    component: Option<String>,
+    segments: Vec<PathSegment>,
+    selector: Option<String>,
+    html: Option<String>,
+    source: Option<Box<dyn std::error::Error + Send + Sync + 'static>>,
+    related: Vec<ComponentError>,
+}
+
+impl ComponentError {
+    pub fn new(kind: ErrorKind) -> Self {
+        Self(Box::new(Inner {
+            kind,
+            component: None,
+            segments: Vec::new(),
+            selector: None,
+            html: None,
+            source: None,
+            related: Vec::new(),
+        }))
+    }
+
+    pub fn missing(expected: Count, found: usize) -> Self {
+        Self::new(ErrorKind::Missing { expected, found })
+    }
+
+    pub fn too_many(expected: Count, found: usize) -> Self {
+        Self::new(ErrorKind::TooMany { expected, found })
+    }
+
+    pub fn missing_value(what: impl Into<String>) -> Self {
+        Self::new(ErrorKind::MissingValue { what: what.into() })
+    }
+
+    pub fn parse(text: impl Into<String>, expected: impl Into<Cow<'static, str>>) -> Self {
+        Self::new(ErrorKind::Parse {
+            text: text.into(),
+            expected: expected.into(),
+        })
+    }
+
+    /// all the `errors` together, a single error is returned as is
+    pub fn multiple(mut errors: Vec<ComponentError>) -> Self {
+        match errors.len() {
+            1 => errors.remove(0),
+            _ => Self::new(ErrorKind::Multiple).with_related(errors),
+        }
+    }
+
+    pub fn with_source(mut self, source: impl Into<Box<dyn std::error::Error + Send + Sync + 'static>>) -> Self {
+        self.0.source = Some(source.into());
+        self
+    }
+
+    /// errors explaining this one, e.g. why every variant of an enum failed
+    pub fn with_related(mut self, related: Vec<ComponentError>) -> Self {
+        self.0.related = related;
+        self
+    }
+
+    /// prepends a field (or enum variant) to the path
+    pub fn at_field(self, field: impl Into<String>) -> Self {
+        self.prepend(PathSegment::Field(field.into()))
+    }
+
+    /// prepends an item index to the path
+    pub fn at_index(self, index: usize) -> Self {
+        self.prepend(PathSegment::Index(index))
+    }
+
+    fn prepend(mut self, segment: PathSegment) -> Self {
+        self.0.segments.insert(0, segment.clone());
+        self.0.related = std::mem::take(&mut self.0.related)
+            .into_iter()
+            .map(|related| related.prepend(segment.clone()))
+            .collect();
+        self
+    }
+
+    /// names the component the path starts at, replacing the name of any nested component
+    pub fn in_component(mut self, component: &str) -> Self {
+        self.0.component = Some(component.to_owned());
+        self.0.related = std::mem::take(&mut self.0.related)
+            .into_iter()
+            .map(|related| related.in_component(component))
+            .collect();
+        self
+    }
+
+    /// records the selector of the failing field and the html it was evaluated on, unless a nested field already did
+    pub fn located(mut self, selector: &str, element: ElementRef<'_>) -> Self {
+        if self.0.selector.is_none() {
+            self.0.selector = Some(selector.to_owned());
+            self.0.html = Some(element.html());
+        }
+        self
+    }
+
+    pub fn kind(&self) -> &ErrorKind {
+        &self.0.kind
+    }
+
+    /// `Page::items[3]::price`
+    pub fn path(&self) -> String {
+        self.0
+            .component
+            .iter()
+            .map(ToString::to_string)
+            .chain(self.0.segments.iter().map(ToString::to_string))
+            .join("")
+    }
+
+    pub fn component(&self) -> Option<&str> {
+        self.0.component.as_deref()
+    }
+
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.0.segments
+    }
+
+    /// selector of the innermost failing field
+    pub fn selector(&self) -> Option<&str> {
+        self.0.selector.as_deref()
+    }
+
+    /// html the selector was evaluated on
+    pub fn html(&self) -> Option<&str> {
+        self.0.html.as_deref()
+    }
+
+    pub fn related(&self) -> &[ComponentError] {
+        &self.0.related
+    }
+}
+
+impl fmt::Display for ComponentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = self.path();
+        if !path.is_empty() {
+            write!(f, "{path}: ")?;
+        }
+        write!(f, "{}", self.0.kind)?;
+        if let Some(selector) = &self.0.selector {
+            write!(f, " (selector: {selector})")?;
+        }
+        if let Some(source) = &self.0.source {
+            write!(f, ": {source}")?;
+        }
+        self.0
+            .related
+            .iter()
+            .try_for_each(|related| write!(f, "\n- {}", related.to_string().replace('\n', "\n  ")))
+    }
+}
+
+impl std::error::Error for ComponentError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.0.source.as_deref().map(|source| source as _)
+    }
+}
+
+/// custom `map` functions may keep returning `anyhow::Result`
+impl From<anyhow::Error> for ComponentError {
+    fn from(error: anyhow::Error) -> Self {
+        Self::new(ErrorKind::Custom).with_source(error)
+    }
+}
@@ -0,0 +1,41 @@
+//! Extractors behind the `attr`, `inner_html`, `outer_html`, `own_text` and `regex` field shorthands,
+//! every one of them (apart from `regex`) can be used as a `map` as well.
+use {
+    crate::{ComponentError, Result, TryFromCaptures, TryFromText},
+    itertools::Itertools,
+    scraper::ElementRef,
+    std::borrow::Cow,
+};
+
+/// HTML of the element's children
+pub fn inner_html<'document, T: TryFromText<'document>>(el: ElementRef<'document>) -> Result<T> {
+    T::try_from_text(Cow::Owned(el.inner_html()))
+}
+
+/// HTML of the element itself, including its tag
+pub fn outer_html<'document, T: TryFromText<'document>>(el: ElementRef<'document>) -> Result<T> {
+    T::try_from_text(Cow::Owned(el.html()))
+}
+
+/// Text of the element's direct text children only, text of nested elements is skipped.
+/// Borrowed from the document when there is a single text node.
+pub fn own_text<'document, T: TryFromText<'document>>(el: ElementRef<'document>) -> Result<T> {
+    let mut nodes = el
+        .children()
+        .filter_map(|node| node.value().as_text())
+        .map(|text| &**text);
+    let text = match (nodes.next(), nodes.next()) {
+        (None, _) => Cow::Borrowed(""),
+        (Some(text), None) => Cow::Borrowed(text),
+        (Some(first), Some(second)) => Cow::Owned([first, second].into_iter().chain(nodes).join("")),
+    };
+    T::try_from_text(text)
+}
+
+/// Runs the regex on the text, the value is taken out of the capture groups
+pub fn regex<'document, T: TryFromCaptures<'document>>(text: Cow<'document, str>, regex: &regex::Regex) -> Result<T> {
+    regex
+        .captures(&text)
+        .ok_or_else(|| ComponentError::parse(&*text, format!("a match of regex `{regex}`")))
+        .and_then(|captures| T::try_from_captures(&text, &captures))
+}
@@ -0,0 +1,39 @@
+//! Selectors shared by every component of the program - each distinct selector string is parsed once, the first time
+//! any component needs it, and the generated code keeps the `&'static` references in its own `LazyLock`.
+use {
+    scraper::{Selector, selector::Simple},
+    selectors::parser::{ParseRelative, SelectorList},
+    std::{
+        collections::HashMap,
+        sync::{Mutex, OnceLock},
+    },
+};
+
+type Interned<T> = OnceLock<Mutex<HashMap<&'static str, &'static T>>>;
+
+static SELECTORS: Interned<Selector> = OnceLock::new();
+static SELECTOR_LISTS: Interned<SelectorList<Simple>> = OnceLock::new();
+
+/// the value parsed out of `key` the first time it's interned, leaked for the rest of the program
+fn intern<T>(interned: &'static Interned<T>, key: &'static str, parse: impl FnOnce(&str) -> T) -> &'static T {
+    interned
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .entry(key)
+        .or_insert_with(|| Box::leak(Box::new(parse(key))))
+}
+
+/// `Selector` for `selector`, parsed once for all the components using it - panics on invalid selectors,
+/// the derive validates them at compile time
+pub fn selector(selector: &'static str) -> &'static Selector {
+    intern(&SELECTORS, selector, |selector| Selector::parse(selector).expect("validated at compile time"))
+}
+
+/// `selector` as matched by the single-pass `Plan`, parsed once for all the components using it
+pub(crate) fn selector_list(selector: &'static str) -> &'static SelectorList<Simple> {
+    intern(&SELECTOR_LISTS, selector, |selector| {
+        let mut input = cssparser::ParserInput::new(selector);
+        SelectorList::parse(&scraper::selector::Parser, &mut cssparser::Parser::new(&mut input), ParseRelative::No).expect("validated at compile time")
+    })
+}
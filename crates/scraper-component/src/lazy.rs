@@ -0,0 +1,178 @@
+//! `many` fields parsed on demand - the component only keeps the matched elements, items are parsed while iterating,
+//! so huge listings can be streamed or abandoned early instead of being materialised up front.
+use {
+    crate::{FieldContext, Result, TryCollectFrom, TryFromElement},
+    itertools::Itertools,
+    scraper::ElementRef,
+    std::{fmt, iter::Enumerate, marker::PhantomData},
+};
+
+/// `#[component(selector = "tr", many)] rows: Lazy<'d, Row>` - the matched elements, each parsed into `T` when iterated.
+/// Errors of the items are only raised while iterating, located as if they failed the field (`Listing::rows[3]::price`).
+pub struct Lazy<'document, T> {
+    elements: Vec<ElementRef<'document>>,
+    field: Option<FieldContext<'document>>,
+    item: PhantomData<fn() -> T>,
+}
+
+/// Matched element of a `Lazy` field, along with the field it's collected into when extracted by a component
+#[derive(Debug, Clone, Copy)]
+pub struct Pending<'document> {
+    element: ElementRef<'document>,
+    field: Option<FieldContext<'document>>,
+}
+
+impl<'document> TryFromElement<'document> for Pending<'document> {
+    fn try_from_element(element: ElementRef<'document>) -> Result<Self> {
+        Ok(Self { element, field: None })
+    }
+
+    fn try_from_item(element: ElementRef<'document>, field: FieldContext<'document>, _index: usize) -> Result<Self> {
+        Ok(Self { element, field: Some(field) })
+    }
+}
+
+impl<'document, T> Lazy<'document, T>
+where
+    T: TryFromElement<'document>,
+{
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// matched elements, not parsed yet
+    pub fn elements(&self) -> &[ElementRef<'document>] {
+        &self.elements
+    }
+
+    /// parses the item at `index` only
+    pub fn get(&self, index: usize) -> Option<Result<T>> {
+        self.elements
+            .get(index)
+            .map(|&element| parse(self.field, index, element))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Result<T>> + '_ {
+        self.elements
+            .iter()
+            .enumerate()
+            .map(|(index, &element)| parse(self.field, index, element))
+    }
+}
+
+fn parse<'document, T>(field: Option<FieldContext<'document>>, index: usize, element: ElementRef<'document>) -> Result<T>
+where
+    T: TryFromElement<'document>,
+{
+    T::try_from_element(element).map_err(|error| match field {
+        Some(field) => field.locate(index, error),
+        None => error.at_index(index),
+    })
+}
+
+impl<'document, T> TryCollectFrom<Pending<'document>> for Lazy<'document, T> {
+    fn try_collect<I>(from: I) -> Result<Self>
+    where
+        I: Iterator<Item = Result<Pending<'document>>>,
+    {
+        let mut field = None;
+        let elements = from.process_results(|pending| {
+            pending
+                .inspect(|pending| field = field.or(pending.field))
+                .map(|pending| pending.element)
+                .collect()
+        })?;
+        Ok(Self {
+            elements,
+            field,
+            item: PhantomData,
+        })
+    }
+}
+
+impl<'document, T> IntoIterator for Lazy<'document, T>
+where
+    T: TryFromElement<'document>,
+{
+    type Item = Result<T>;
+    type IntoIter = ComponentIter<'document, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        ComponentIter {
+            elements: self.elements.into_iter().enumerate(),
+            field: self.field,
+            item: PhantomData,
+        }
+    }
+}
+
+impl<T> Clone for Lazy<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            elements: self.elements.clone(),
+            field: self.field,
+            item: PhantomData,
+        }
+    }
+}
+
+impl<T> fmt::Debug for Lazy<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Lazy")
+            .field("item", &std::any::type_name::<T>())
+            .field("elements", &self.elements.len())
+            .finish()
+    }
+}
+
+/// Iterator parsing the matched elements one at a time, usable as a field type on its own
+/// (`#[component(selector = "tr", many)] rows: ComponentIter<'d, Row>`) when the items are consumed once
+pub struct ComponentIter<'document, T> {
+    elements: Enumerate<std::vec::IntoIter<ElementRef<'document>>>,
+    field: Option<FieldContext<'document>>,
+    item: PhantomData<fn() -> T>,
+}
+
+impl<'document, T> Iterator for ComponentIter<'document, T>
+where
+    T: TryFromElement<'document>,
+{
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.elements
+            .next()
+            .map(|(index, element)| parse(self.field, index, element))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.elements.size_hint()
+    }
+}
+
+impl<'document, T> ExactSizeIterator for ComponentIter<'document, T> where T: TryFromElement<'document> {}
+
+impl<'document, T> TryCollectFrom<Pending<'document>> for ComponentIter<'document, T>
+where
+    T: TryFromElement<'document>,
+{
+    fn try_collect<I>(from: I) -> Result<Self>
+    where
+        I: Iterator<Item = Result<Pending<'document>>>,
+    {
+        Lazy::try_collect(from).map(Lazy::into_iter)
+    }
+}
+
+impl<T> fmt::Debug for ComponentIter<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ComponentIter")
+            .field("item", &std::any::type_name::<T>())
+            .field("remaining", &self.elements.len())
+            .finish()
+    }
+}
@@ -1,14 +1,22 @@
 #![allow(incomplete_features)]
 #![feature(array_try_from_fn)]
-#![feature(array_try_map)]
 #![feature(adt_const_params)]
 #![feature(unsized_const_params)]
 
-pub use {anyhow, nonempty::NonEmpty, scraper, scraper_component_macros::Component};
+pub use {
+    anyhow,
+    collect::{Bounded, Counted, DuplicateKeys, Tolerant, TryCollectDistinct},
+    error::{ComponentError, Count, ErrorKind, PathSegment, Result},
+    intern::selector,
+    lazy::{ComponentIter, Lazy},
+    nonempty::NonEmpty,
+    regex,
+    scraper,
+    scraper_component_macros::{Captures, Component},
+};
 use {
-    anyhow::{Context, Result},
     itertools::Itertools,
-    std::{collections::VecDeque, convert::identity, str::FromStr},
+    std::{borrow::Cow, collections::VecDeque, str::FromStr},
 };
 
 /// Exactly one element
@@ -49,15 +57,58 @@ impl<T> TryCollectFrom<T> for Option<T> {
     }
 }
 
+/// `#[component(at_most_one)]` - like collecting into `Option<T>`, but extra elements are an error instead of being ignored
+pub fn try_collect_at_most_one<T, I>(mut from: I) -> Result<Option<T>>
+where
+    I: Iterator<Item = Result<T>>,
+{
+    let first = from.next().transpose()?;
+    match from.next() {
+        None => Ok(first),
+        Some(_) => Err(ComponentError::too_many(Count::at_most(1), 2 + from.count())),
+    }
+}
+
 impl_normal_collect!(Vec<T>);
 impl_normal_collect!(VecDeque<T>);
+impl_normal_collect!(Box<[T]>);
+impl_normal_collect!(std::sync::Arc<[T]>);
+
+/// spills onto the heap past its inline capacity, just like when collected normally
+#[cfg(feature = "smallvec")]
+impl<A> TryCollectFrom<A::Item> for smallvec::SmallVec<A>
+where
+    A: smallvec::Array,
+{
+    fn try_collect<I>(from: I) -> Result<Self>
+    where
+        I: Iterator<Item = Result<A::Item>>,
+    {
+        from.process_results(|r| r.collect())
+    }
+}
+
+/// more than `CAP` elements are an error, like for arrays
+#[cfg(feature = "arrayvec")]
+impl<T, const CAP: usize> TryCollectFrom<T> for arrayvec::ArrayVec<T, CAP> {
+    fn try_collect<I>(mut from: I) -> Result<Self>
+    where
+        I: Iterator<Item = Result<T>>,
+    {
+        let output = from.by_ref().take(CAP).try_collect::<_, Self, _>()?;
+        match from.next() {
+            Some(_) => Err(ComponentError::too_many(Count::at_most(CAP), CAP + 1 + from.count())),
+            None => Ok(output),
+        }
+    }
+}
 
 impl<T> TryCollectFrom<T> for nonempty::NonEmpty<T> {
     fn try_collect<I>(from: I) -> Result<Self>
     where
         I: Iterator<Item = Result<T>>,
     {
-        from.process_results(|r| nonempty::NonEmpty::collect(r).context("expected at least one element"))
+        from.process_results(|r| nonempty::NonEmpty::collect(r).ok_or_else(|| ComponentError::missing(Count::at_least(1), 0)))
             .flatten()
     }
 }
@@ -69,16 +120,71 @@ impl<const SIZE: usize, T> TryCollectFrom<T> for [T; SIZE] {
     {
         std::array::try_from_fn(|idx| {
             from.next()
-                .with_context(|| format!("could not extract element at index [{idx}]"))
+                .ok_or_else(|| ComponentError::missing(Count::exactly(SIZE), idx))
+                .flatten()
         })
-        .and_then(|e| e.try_map(identity))
         .and_then(|output| match from.next() {
-            Some(_) => Err(anyhow::anyhow!("found extra element")),
+            Some(_) => Err(ComponentError::too_many(Count::exactly(SIZE), SIZE + 1 + from.count())),
             None => Ok(output),
         })
     }
 }
 
+/// `#[component(many, skip_errors)]` - the item, or `None` once the failure is reported as a `tracing` warning
+/// with the item's index and (the beginning of) its html. Collect into `Tolerant<C>` to get the failures instead.
+pub fn skip_failed<T>(field: &str, index: usize, element: scraper::ElementRef<'_>, item: Result<T>) -> Option<T> {
+    item.map_err(|error| error.at_index(index))
+        .inspect_err(|error| {
+            let html = element.html();
+            let html = match html.char_indices().nth(SNIPPET_LENGTH) {
+                Some((end, _)) => format!("{}...", &html[..end]),
+                None => html,
+            };
+            tracing::warn!(field, index, html, %error, "skipping item which failed to parse");
+        })
+        .ok()
+}
+
+/// characters of html reported with a skipped item
+const SNIPPET_LENGTH: usize = 200;
+
+/// Collecting `Result`s keeps the items which failed (`Vec<Result<Row>>`), the error of every item is reported
+/// at its own index rather than failing the whole field
+impl<'document, T> TryFromElement<'document> for Result<T>
+where
+    T: TryFromElement<'document>,
+{
+    fn try_from_element(element: scraper::ElementRef<'document>) -> Result<Self> {
+        Ok(T::try_from_element(element))
+    }
+
+    fn try_from_item(element: scraper::ElementRef<'document>, field: FieldContext<'document>, index: usize) -> Result<Self> {
+        Ok(T::try_from_element(element).map_err(|error| field.locate(index, error)))
+    }
+}
+
+/// The `many` field an item is collected into - items keeping their failure for later (`Result<T>`) give it
+/// the path, selector and html it gets when the whole field fails (`Listing::rows[2]::price`)
+#[derive(Debug, Clone, Copy)]
+pub struct FieldContext<'document> {
+    pub component: &'static str,
+    pub field: &'static str,
+    pub selector: &'static str,
+    /// element of the component the field is extracted from
+    pub element: scraper::ElementRef<'document>,
+}
+
+impl FieldContext<'_> {
+    /// error of the item at `index`, as if it failed the field
+    pub fn locate(&self, index: usize, error: ComponentError) -> ComponentError {
+        error
+            .at_index(index)
+            .at_field(self.field)
+            .located(self.selector, self.element)
+            .in_component(self.component)
+    }
+}
+
 pub fn try_from_element<'document, T>(element: scraper::ElementRef<'document>) -> Result<T>
 where
     T: TryFromElement<'document> + Sized + 'document,
@@ -86,51 +192,236 @@ where
     T::try_from_element(element)
 }
 
+/// Item `index` of a `many` field extracted without `map`/`attr`/..., see `TryFromElement::try_from_item`
+pub fn try_from_item<'document, T>(element: scraper::ElementRef<'document>, field: FieldContext<'document>, index: usize) -> Result<T>
+where
+    T: TryFromElement<'document>,
+{
+    T::try_from_item(element, field, index)
+}
+
+/// Elements matching the first of the `selectors` that matches anything at all, falls back to the first selector when none does
+/// (`#[component(selector = "...", fallback = [...])]`)
+pub fn select_first_matching<'document, 'selector>(
+    element: scraper::ElementRef<'document>,
+    selectors: &[&'selector scraper::Selector],
+) -> scraper::element_ref::Select<'document, 'selector> {
+    selectors
+        .iter()
+        .find(|selector| element.select(selector).next().is_some())
+        .or(selectors.first())
+        .map(|selector| element.select(selector))
+        .expect("at least one selector")
+}
+
+/// Root element of a component declared with `#[component(root = "...")]` - the element itself when it matches the selector,
+/// its only matching descendant otherwise
+pub fn root<'document>(element: scraper::ElementRef<'document>, selector: &scraper::Selector) -> Result<scraper::ElementRef<'document>> {
+    if selector.matches(&element) {
+        return Ok(element);
+    }
+    let mut matches = element.select(selector);
+    match (matches.next(), matches.next()) {
+        (Some(root), None) => Ok(root),
+        (None, _) => Err(ComponentError::missing(Count::exactly(1), 0)),
+        (Some(_), Some(_)) => Err(ComponentError::too_many(Count::exactly(1), 2 + matches.count())),
+    }
+}
+
 pub trait TryFromElement<'document>: Sized + 'document {
     fn try_from_element(element: scraper::ElementRef<'document>) -> Result<Self>;
+
+    /// Item `index` of the `many` `field`, parsed like any other element - the errors are attributed to the item
+    /// by the field, only items which keep their errors instead of failing need to know where they are
+    fn try_from_item(element: scraper::ElementRef<'document>, _field: FieldContext<'document>, _index: usize) -> Result<Self> {
+        Self::try_from_element(element)
+    }
 }
 
+/// Components declared with `#[component(partial)]` - every field is extracted on its own, so the ones that parsed
+/// can be used even when others failed
+pub trait PartialComponent<'document>: TryFromElement<'document> {
+    /// generated `{Component}Partial` struct, every field wrapped in a `Result`
+    type Partial;
+
+    /// only errors not attributable to a single field (e.g. the `root` selector) fail the whole component
+    fn try_from_element_partial(element: scraper::ElementRef<'document>) -> Result<Self::Partial>;
+}
+
+/// `Partial<'_, Product>` is the generated `ProductPartial`
+pub type Partial<'document, T> = <T as PartialComponent<'document>>::Partial;
+
 impl<'document> TryFromElement<'document> for scraper::ElementRef<'document> {
     fn try_from_element(element: scraper::ElementRef<'document>) -> Result<Self> {
         Ok(element)
     }
 }
 
-impl<'document> TryFromElement<'document> for String {
+/// Key and value parsed from the same element, e.g. a `(SpecName, SpecValue)` row collected into a map
+impl<'document, K, V> TryFromElement<'document> for (K, V)
+where
+    K: TryFromElement<'document>,
+    V: TryFromElement<'document>,
+{
     fn try_from_element(element: scraper::ElementRef<'document>) -> Result<Self> {
-        Ok(element.text().join(""))
+        Ok((K::try_from_element(element)?, V::try_from_element(element)?))
+    }
+
+    fn try_from_item(element: scraper::ElementRef<'document>, field: FieldContext<'document>, index: usize) -> Result<Self> {
+        Ok((K::try_from_item(element, field, index)?, V::try_from_item(element, field, index)?))
+    }
+}
+
+/// Text content of an element, borrowed straight from the document when it consists of a single text node
+pub fn text<'document>(element: scraper::ElementRef<'document>) -> Cow<'document, str> {
+    let mut nodes = element.text();
+    match (nodes.next(), nodes.next()) {
+        (None, _) => Cow::Borrowed(""),
+        (Some(text), None) => Cow::Borrowed(text),
+        (Some(first), Some(second)) => Cow::Owned([first, second].into_iter().chain(nodes).join("")),
+    }
+}
+
+/// Types that can be extracted out of a piece of text (element text content, attribute value...)
+pub trait TryFromText<'document>: Sized + 'document {
+    fn try_from_text(text: Cow<'document, str>) -> Result<Self>;
+
+    /// called when there is no text to begin with (e.g. a missing attribute), only `Option<T>` accepts that -
+    /// `what` describes the missing value (`attribute "href"`)
+    fn try_from_missing_text(what: &str) -> Result<Self> {
+        Err(ComponentError::missing_value(what))
     }
 }
 
+impl<'document, T> TryFromText<'document> for Option<T>
+where
+    T: TryFromText<'document>,
+{
+    fn try_from_text(text: Cow<'document, str>) -> Result<Self> {
+        T::try_from_text(text).map(Some)
+    }
+
+    fn try_from_missing_text(_: &str) -> Result<Self> {
+        Ok(None)
+    }
+}
+
+impl<'document> TryFromText<'document> for String {
+    fn try_from_text(text: Cow<'document, str>) -> Result<Self> {
+        Ok(text.into_owned())
+    }
+}
+
+impl<'document> TryFromText<'document> for Cow<'document, str> {
+    fn try_from_text(text: Cow<'document, str>) -> Result<Self> {
+        Ok(text)
+    }
+}
+
+impl<'document> TryFromText<'document> for &'document str {
+    fn try_from_text(text: Cow<'document, str>) -> Result<Self> {
+        match text {
+            Cow::Borrowed(text) => Ok(text),
+            Cow::Owned(text) => Err(ComponentError::parse(
+                text,
+                "&str (text spans multiple nodes and can't be borrowed, use String or Cow<str> instead)",
+            )),
+        }
+    }
+}
+
+/// Types that can be extracted out of a regex match, see `#[component(regex = "...")]`.
+/// Every `TryFromText` type takes the first capture group (or the whole match when there are no groups),
+/// `#[derive(Captures)]` maps named groups onto struct fields.
+pub trait TryFromCaptures<'document>: Sized + 'document {
+    // whether the text is borrowed decides whether the captures can be borrowed too
+    #[allow(clippy::ptr_arg)]
+    fn try_from_captures(text: &Cow<'document, str>, captures: &regex::Captures<'_>) -> Result<Self>;
+}
+
+impl<'document, T> TryFromCaptures<'document> for T
+where
+    T: TryFromText<'document>,
+{
+    fn try_from_captures(text: &Cow<'document, str>, captures: &regex::Captures<'_>) -> Result<Self> {
+        match captures.len() {
+            1 => captured(text, captures.get(0)),
+            _ => captured(text, captures.get(1)),
+        }
+    }
+}
+
+/// Value of a capture group, borrowed from the document whenever the matched text was
+#[allow(clippy::ptr_arg)]
+pub fn captured<'document, T>(text: &Cow<'document, str>, group: Option<regex::Match<'_>>) -> Result<T>
+where
+    T: TryFromText<'document>,
+{
+    match (text, group) {
+        (_, None) => T::try_from_missing_text("capture group"),
+        (Cow::Borrowed(text), Some(group)) => T::try_from_text(Cow::Borrowed(&text[group.range()])),
+        (Cow::Owned(_), Some(group)) => T::try_from_text(Cow::Owned(group.as_str().to_owned())),
+    }
+}
+
+macro_rules! impl_text_element {
+    ($ty:ty) => {
+        impl<'document> TryFromElement<'document> for $ty {
+            fn try_from_element(element: scraper::ElementRef<'document>) -> Result<Self> {
+                TryFromText::try_from_text(text(element))
+            }
+        }
+    };
+}
+
+impl_text_element!(String);
+impl_text_element!(Cow<'document, str>);
+impl_text_element!(&'document str);
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Hardcoded<const VALUE: &'static str>;
 
-impl<'document, const VALUE: &'static str> TryFromElement<'document> for Hardcoded<VALUE> {
-    fn try_from_element(element: scraper::ElementRef<'document>) -> Result<Self> {
-        let text = element.text().join("");
+impl<'document, const VALUE: &'static str> TryFromText<'document> for Hardcoded<VALUE> {
+    fn try_from_text(text: Cow<'document, str>) -> Result<Self> {
         if text.trim() == VALUE {
             Ok(Self)
         } else {
-            Err(anyhow::anyhow!("expected {VALUE}, found {text}"))
+            Err(ComponentError::new(ErrorKind::HardcodedMismatch {
+                expected: VALUE,
+                found: text.into_owned(),
+            }))
         }
     }
 }
 
+impl<'document, const VALUE: &'static str> TryFromElement<'document> for Hardcoded<VALUE> {
+    fn try_from_element(element: scraper::ElementRef<'document>) -> Result<Self> {
+        TryFromText::try_from_text(text(element))
+    }
+}
+
 #[derive(Debug)]
 pub struct Parsed<T>(pub T);
 
+impl<'document, T> TryFromText<'document> for Parsed<T>
+where
+    T: FromStr + 'document,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    fn try_from_text(text: Cow<'document, str>) -> Result<Self> {
+        text.parse::<T>()
+            .map_err(|e| ComponentError::parse(&*text, std::any::type_name::<T>()).with_source(e))
+            .map(Parsed)
+    }
+}
+
 impl<'document, T> TryFromElement<'document> for Parsed<T>
 where
     T: FromStr + 'document,
     T::Err: std::error::Error + Send + Sync + 'static,
 {
     fn try_from_element(element: scraper::ElementRef<'document>) -> Result<Self> {
-        element
-            .text()
-            .join("")
-            .parse::<T>()
-            .with_context(|| format!("could not parse into [{}]", std::any::type_name::<Self>()))
-            .map(Parsed)
+        TryFromText::try_from_text(text(element))
     }
 }
 
@@ -147,3 +438,11 @@ where
 }
 
 pub mod attribute;
+pub mod collect;
+pub mod error;
+pub mod extract;
+pub mod intern;
+pub mod lazy;
+pub mod navigate;
+pub mod pipeline;
+pub mod plan;
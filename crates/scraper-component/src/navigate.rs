@@ -0,0 +1,29 @@
+//! Moving up and sideways in the tree, behind the `parent`, `closest`, `next_sibling` and `prev_sibling` field options.
+//! The selector of the field (if any) is then evaluated relative to the element found here.
+use scraper::{ElementRef, Selector};
+
+/// Direct parent element
+pub fn parent(el: ElementRef<'_>) -> Option<ElementRef<'_>> {
+    el.parent().and_then(ElementRef::wrap)
+}
+
+/// The element itself or its nearest ancestor matching the selector (like DOM's `Element.closest`)
+pub fn closest<'document>(el: ElementRef<'document>, selector: &Selector) -> Option<ElementRef<'document>> {
+    std::iter::once(el)
+        .chain(el.ancestors().filter_map(ElementRef::wrap))
+        .find(|element| selector.matches(element))
+}
+
+/// Nearest following sibling element matching the selector
+pub fn next_sibling<'document>(el: ElementRef<'document>, selector: &Selector) -> Option<ElementRef<'document>> {
+    el.next_siblings()
+        .filter_map(ElementRef::wrap)
+        .find(|element| selector.matches(element))
+}
+
+/// Nearest preceding sibling element matching the selector
+pub fn prev_sibling<'document>(el: ElementRef<'document>, selector: &Selector) -> Option<ElementRef<'document>> {
+    el.prev_siblings()
+        .filter_map(ElementRef::wrap)
+        .find(|element| selector.matches(element))
+}
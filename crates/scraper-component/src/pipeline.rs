@@ -0,0 +1,32 @@
+//! Clean-ups of `many` fields between extraction and collection - `dedup` and `dedup_by` drop repeated values,
+//! keeping the first one in document order (`filter`, `sort_by` and `limit` need no helpers).
+use std::{collections::HashSet, hash::Hash};
+
+/// `#[component(many, dedup)]` - values equal to an earlier one are dropped
+pub fn dedup<T>(items: Vec<T>) -> Vec<T>
+where
+    T: Hash + Eq,
+{
+    let mut seen = HashSet::new();
+    let keep = items
+        .iter()
+        .map(|item| seen.insert(item))
+        .collect::<Vec<_>>();
+    items
+        .into_iter()
+        .zip(keep)
+        .filter_map(|(item, keep)| keep.then_some(item))
+        .collect()
+}
+
+/// `#[component(many, dedup_by = path::to_key_fn)]` - values with the same key as an earlier one are dropped
+pub fn dedup_by_key<T, K>(items: Vec<T>, mut key: impl FnMut(&T) -> K) -> Vec<T>
+where
+    K: Hash + Eq,
+{
+    let mut seen = HashSet::new();
+    items
+        .into_iter()
+        .filter(|item| seen.insert(key(item)))
+        .collect()
+}
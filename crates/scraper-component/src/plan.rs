@@ -0,0 +1,140 @@
+//! Single-pass matching behind derived components - instead of every field walking the subtree with its own selector,
+//! all the distinct selectors of a component are matched during one walk and the matches are handed out to the fields.
+use {
+    crate::intern,
+    ego_tree::NodeId,
+    scraper::{ElementRef, selector::Simple},
+    selectors::{
+        Element,
+        context::{MatchingContext, MatchingForInvalidation, MatchingMode, NeedsSelectorFlags, QuirksMode, SelectorCaches},
+        matching::matches_selector_list,
+        parser::SelectorList,
+    },
+    std::cell::RefCell,
+};
+
+/// Distinct selectors of a component (`static ___PLAN: LazyLock<Plan<N>>` in the generated code), each of them parsed
+/// once for all the components using it
+pub struct Plan<const N: usize>([&'static SelectorList<Simple>; N]);
+
+impl<const N: usize> Plan<N> {
+    /// panics on invalid selectors, the derive validates them at compile time
+    pub fn new(selectors: [&'static str; N]) -> Self {
+        Self(selectors.map(intern::selector_list))
+    }
+}
+
+thread_local! {
+    /// Buffers of the `Matches` dropped on this thread, reused by the next parses - nested components take one each
+    /// while their parent holds its own, so the pool grows to the nesting depth and parses stop allocating once warm.
+    /// `cargo bench -p scraper-component-macros-test` puts it within 2% of a fresh `Vec` per parse either way,
+    /// the walk and the matching dominate
+    static BUFFERS: RefCell<Vec<Vec<Match>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Elements matching each selector of the plan, in document order - every match goes into one buffer shared by all the
+/// selectors, each match linking to the next one of its selector, so a parse fills a single `Vec` however many selectors
+/// the component has (instead of one per matched selector), taken from and given back to a per-thread pool
+pub struct Matches<'document, const N: usize> {
+    /// the matched elements are looked up in its tree
+    element: ElementRef<'document>,
+    matches: Vec<Match>,
+    /// first and last match of each selector, `None` when it matched nothing
+    lists: [Option<(usize, usize)>; N],
+}
+
+/// Held by node id rather than `ElementRef`, so buffers outlive the document they were filled from
+#[derive(Clone, Copy)]
+struct Match {
+    element: NodeId,
+    /// next match of the same selector
+    next: Option<usize>,
+}
+
+/// Walks the descendants of `element` once, checking each of them against every selector of the plan
+/// (exactly like `ElementRef::select`, the element itself is not a candidate and `:scope` refers to it) -
+/// the matching context and its caches are shared by the whole walk rather than set up for every check
+pub fn match_all<'document, const N: usize>(element: ElementRef<'document>, plan: &Plan<N>) -> Matches<'document, N> {
+    let mut matches = Matches {
+        element,
+        matches: BUFFERS.with_borrow_mut(Vec::pop).unwrap_or_default(),
+        lists: [None; N],
+    };
+    let mut caches = SelectorCaches::default();
+    let mut context = MatchingContext::new(
+        MatchingMode::Normal,
+        None,
+        &mut caches,
+        QuirksMode::NoQuirks,
+        NeedsSelectorFlags::No,
+        MatchingForInvalidation::No,
+    );
+    context.scope_element = Some(element.opaque());
+    element
+        .descendants()
+        .skip(1)
+        .filter_map(ElementRef::wrap)
+        .for_each(|descendant| {
+            plan.0
+                .iter()
+                .enumerate()
+                .filter(|(_, selectors)| matches_selector_list(selectors, &descendant, &mut context))
+                .for_each(|(selector, _)| matches.push(selector, descendant))
+        });
+    matches
+}
+
+impl<'document, const N: usize> Matches<'document, N> {
+    fn push(&mut self, selector: usize, element: ElementRef<'document>) {
+        let index = self.matches.len();
+        self.matches.push(Match {
+            element: element.id(),
+            next: None,
+        });
+        self.lists[selector] = match self.lists[selector] {
+            Some((first, last)) => {
+                self.matches[last].next = Some(index);
+                Some((first, index))
+            }
+            None => Some((index, index)),
+        };
+    }
+
+    /// Elements of the first of `selectors` (indices into the plan) that matched anything, none otherwise -
+    /// the single-pass counterpart of `select_first_matching`
+    pub fn first_matching(&self, selectors: &[usize]) -> MatchesOf<'_, 'document> {
+        MatchesOf {
+            element: self.element,
+            matches: &self.matches,
+            next: selectors
+                .iter()
+                .find_map(|&selector| self.lists[selector])
+                .map(|(first, _)| first),
+        }
+    }
+}
+
+impl<const N: usize> Drop for Matches<'_, N> {
+    fn drop(&mut self) {
+        let mut matches = std::mem::take(&mut self.matches);
+        matches.clear();
+        BUFFERS.with_borrow_mut(|buffers| buffers.push(matches));
+    }
+}
+
+/// Elements matched by one selector of the plan, following the links between its matches
+pub struct MatchesOf<'matches, 'document> {
+    element: ElementRef<'document>,
+    matches: &'matches [Match],
+    next: Option<usize>,
+}
+
+impl<'document> Iterator for MatchesOf<'_, 'document> {
+    type Item = ElementRef<'document>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Match { element, next } = self.matches[self.next?];
+        self.next = next;
+        self.element.tree().get(element).and_then(ElementRef::wrap)
+    }
+}